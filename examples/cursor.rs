@@ -3,8 +3,12 @@ use std::{
     time::Duration,
 };
 
-use termip::utils::{
-    self, enable_raw_mode, hide_cursor, move_cursor, show_cursor, disable_raw_mode, enter_alternate_buffer, leave_alternate_buffer,
+use termip::{
+    geometry::Position,
+    utils::{
+        self, disable_raw_mode, enable_raw_mode, enter_alternate_buffer, hide_cursor,
+        leave_alternate_buffer, move_cursor, show_cursor,
+    },
 };
 
 fn main() -> std::io::Result<()> {
@@ -14,12 +18,9 @@ fn main() -> std::io::Result<()> {
     enter_alternate_buffer(&mut out)?;
     enable_raw_mode(&mut inp)?;
 
-    let (x, y) = utils::get_size(&out)?;
+    let size = utils::get_size(&out)?;
 
-    let x = x / 2;
-    let y = y / 2;
-
-    move_cursor(&mut out, x, y)?;
+    move_cursor(&mut out, Position::new(size.rows / 2, size.cols / 2))?;
 
     hide_cursor(&mut out)?;
 
@@ -33,14 +34,14 @@ fn main() -> std::io::Result<()> {
 
     std::thread::sleep(Duration::from_secs(1));
 
-    move_cursor(&mut out, 10, 10)?;
-    
+    move_cursor(&mut out, Position::new(10, 10))?;
+
     out.flush()?;
 
     std::thread::sleep(Duration::from_secs(1));
 
     show_cursor(&mut out)?;
-    move_cursor(&mut out, 0, 0)?;
+    move_cursor(&mut out, Position::ORIGIN)?;
     disable_raw_mode(&mut inp)?;
     leave_alternate_buffer(&mut out)?;
 
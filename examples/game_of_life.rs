@@ -1,15 +1,18 @@
 use std::{
     io::{stdin, stdout, Stdin, Stdout, Write},
     time::Instant,
-    usize,
 };
 
 use termip::{
     events::{Event, KeyCode, KeyEvent},
+    geometry::Position,
+    render::{Buffer, Renderer},
+    style::Style,
     utils::{
         disable_raw_mode, enable_raw_mode, enter_alternate_buffer, erase_entire_screen, get_size,
-        leave_alternate_buffer, move_cursor, read_batch, 
-    }, 
+        leave_alternate_buffer, move_cursor, read_batch, supports_synchronized_output,
+        synchronized_update,
+    },
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -37,7 +40,7 @@ struct Game {
 impl Game {
     pub fn new(width: usize, height: usize) -> Self {
         Self {
-            board: vec![Cell::Dead; (width * height) as usize],
+            board: vec![Cell::Dead; width * height],
             width,
             height,
         }
@@ -54,13 +57,13 @@ impl Game {
     fn get(&self, x: usize, y: usize) -> Option<&Cell> {
         let idx = self.calc_idx(x, y)?;
 
-        self.board.get(idx as usize)
+        self.board.get(idx)
     }
 
     fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut Cell> {
         let idx = self.calc_idx(x, y)?;
 
-        self.board.get_mut(idx as usize)
+        self.board.get_mut(idx)
     }
 
     fn set(&mut self, x: usize, y: usize, cell: Cell) {
@@ -89,7 +92,7 @@ impl Game {
         };
 
         match neighbourhood {
-            2 => cell.clone(),
+            2 => *cell,
             3 => Cell::Alive,
             _ => Cell::Dead,
         }
@@ -99,29 +102,23 @@ impl Game {
         let buf = self.clone();
 
         for idx in 0..buf.board.len() {
-            let x = idx % buf.width as usize;
-            let y = idx / buf.width as usize;
+            let x = idx % buf.width;
+            let y = idx / buf.width;
 
             let cell = buf.new_state(x, y);
             self.set(x, y, cell);
         }
     }
 
-    fn display(&self) -> String {
-        let mut buf = String::new();
-
+    fn draw(&self, buffer: &mut Buffer) {
         for (idx, cell) in self.board.iter().enumerate() {
-            if idx % self.width as usize == 0 && idx != 0 {
-                buf.push_str("\n");
-            }
+            let x = (idx % self.width) as u16;
+            let y = (idx / self.width) as u16;
 
-            match cell {
-                Cell::Alive => buf.push_str("█"),
-                Cell::Dead => buf.push_str(" "),
+            if *cell == Cell::Alive {
+                buffer.set_string(Position::new(y, x), "█", Style::default());
             }
         }
-
-        buf
     }
 }
 
@@ -136,19 +133,15 @@ struct Cursor {
 impl Cursor {
     fn new(x_max: u16, y_max: u16) -> Self {
         Self {
-            x: 1,
-            y: 1,
+            x: 0,
+            y: 0,
             x_max,
             y_max,
         }
     }
 
     fn left(&mut self) {
-        let e = self.x.saturating_sub(1);
-
-        if e >= 1 {
-            self.x = e;
-        }
+        self.x = self.x.saturating_sub(1);
     }
 
     fn right(&mut self) {
@@ -160,11 +153,7 @@ impl Cursor {
     }
 
     fn up(&mut self) {
-        let e = self.y.saturating_sub(1);
-
-        if e >= 1 {
-            self.y = e;
-        }
+        self.y = self.y.saturating_sub(1);
     }
 
     fn down(&mut self) {
@@ -175,12 +164,8 @@ impl Cursor {
         }
     }
 
-    fn x(&self) -> u16 {
-        self.x
-    }
-
-    fn y(&self) -> u16 {
-        self.y
+    fn position(&self) -> Position {
+        Position::new(self.y, self.x)
     }
 }
 
@@ -208,7 +193,7 @@ fn un_setup(out: &mut Stdout, inp: &mut Stdin) -> std::io::Result<()> {
 }
 
 fn setup(out: &mut Stdout, inp: &mut Stdin) -> std::io::Result<()> {
-    move_cursor(out, 1, 1)?;
+    move_cursor(out, Position::ORIGIN)?;
 
     enable_raw_mode(inp)?;
 
@@ -228,10 +213,13 @@ fn main() -> std::io::Result<()> {
     setup(&mut out, &mut inp)?;
     out.flush()?;
 
-    let (height, width) = get_size(&mut out)?;
+    let synchronized = supports_synchronized_output(&mut out, &mut inp)?;
+
+    let size = get_size(&out)?;
 
-    let mut cursor = Cursor::new(width, height);
-    let mut game = Game::new(width as usize, height as usize);
+    let mut cursor = Cursor::new(size.cols, size.rows);
+    let mut game = Game::new(size.cols as usize, size.rows as usize);
+    let mut renderer = Renderer::new(size);
     let mut state = State::Editing;
 
     let mut last_time: Instant;
@@ -252,7 +240,7 @@ fn main() -> std::io::Result<()> {
                     ..
                 }) => {
                     cursor.left();
-                    move_cursor(&mut out, cursor.y(), cursor.x())?;
+                    move_cursor(&mut out, cursor.position())?;
                     out.flush()?;
                 }
                 Event::Key(KeyEvent {
@@ -260,7 +248,7 @@ fn main() -> std::io::Result<()> {
                     ..
                 }) => {
                     cursor.right();
-                    move_cursor(&mut out, cursor.y(), cursor.x())?;
+                    move_cursor(&mut out, cursor.position())?;
                     out.flush()?;
                 }
                 Event::Key(KeyEvent {
@@ -268,7 +256,7 @@ fn main() -> std::io::Result<()> {
                     ..
                 }) => {
                     cursor.up();
-                    move_cursor(&mut out, cursor.y(), cursor.x())?;
+                    move_cursor(&mut out, cursor.position())?;
                     out.flush()?;
                 }
                 Event::Key(KeyEvent {
@@ -276,20 +264,20 @@ fn main() -> std::io::Result<()> {
                     ..
                 }) => {
                     cursor.down();
-                    move_cursor(&mut out, cursor.y(), cursor.x())?;
+                    move_cursor(&mut out, cursor.position())?;
                     out.flush()?;
                 }
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('a'),
                     ..
                 }) => {
-                    game.set((cursor.x() - 1) as usize, (cursor.y() - 1) as usize, Cell::Alive);
+                    game.set(cursor.x as usize, cursor.y as usize, Cell::Alive);
                 }
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('d'),
                     ..
                 }) => {
-                    game.set((cursor.x() - 1) as usize, (cursor.y() - 1) as usize, Cell::Dead);
+                    game.set(cursor.x as usize, cursor.y as usize, Cell::Dead);
                 }
                 Event::Key(KeyEvent {
                     code: KeyCode::Char('e'),
@@ -307,13 +295,13 @@ fn main() -> std::io::Result<()> {
             }
         }
 
-        move_cursor(&mut out, 1, 1)?;
-
-        write!(out, "{}", game.display())?;
+        game.draw(renderer.buffer_mut());
 
-        move_cursor(&mut out, cursor.y(), cursor.x())?;
+        synchronized_update(&mut out, synchronized, |out| {
+            renderer.render(out)?;
 
-        out.flush()?;
+            move_cursor(out, cursor.position())
+        })?;
 
         std::thread::sleep(Instant::now() - last_time);
     }
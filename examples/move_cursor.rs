@@ -3,10 +3,13 @@ use std::{
     time::Duration,
 };
 
-use termip::utils::{enable_raw_mode, erase_entire_screen, move_cursor, get_size, disable_raw_mode};
+use termip::{
+    geometry::Position,
+    utils::{disable_raw_mode, enable_raw_mode, erase_entire_screen, get_size, move_cursor},
+};
 
-fn move_and_wait(s: &mut Stdout, line: u16, column: u16) -> std::io::Result<()> {
-    move_cursor(s, line, column)?;
+fn move_and_wait(s: &mut Stdout, row: u16, col: u16) -> std::io::Result<()> {
+    move_cursor(s, Position::new(row, col))?;
 
     s.flush()?;
 
@@ -21,7 +24,7 @@ fn main() -> std::io::Result<()> {
 
     erase_entire_screen(&mut out)?;
 
-    move_cursor(&mut out, 0, 0)?;
+    move_cursor(&mut out, Position::ORIGIN)?;
 
     enable_raw_mode(&mut inp)?;
 
@@ -29,14 +32,16 @@ fn main() -> std::io::Result<()> {
 
     for _ in 0..3 {
         let size = get_size(&out)?;
+        let bottom = size.rows.saturating_sub(1);
+        let right = size.cols.saturating_sub(1);
 
         move_and_wait(&mut out, 0, 0)?;
 
-        move_and_wait(&mut out, size.0, 0)?;
+        move_and_wait(&mut out, bottom, 0)?;
 
-        move_and_wait(&mut out, size.0, size.1)?;
+        move_and_wait(&mut out, bottom, right)?;
 
-        move_and_wait(&mut out, 0, size.1)?;
+        move_and_wait(&mut out, 0, right)?;
     }
 
     disable_raw_mode(&mut inp)?;
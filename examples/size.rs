@@ -7,7 +7,7 @@ fn main() -> std::io::Result<()> {
 
     let s = get_size(&out)?;
 
-    println!("{}:{}", s.0, s.1);
+    println!("{}:{}", s.rows, s.cols);
 
     Ok(())
 }
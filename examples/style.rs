@@ -1,18 +1,54 @@
 use std::io::{stdout, Write};
 
-use termip::{utils::set_fg, style::Color};
+use termip::{
+    capabilities::Capabilities,
+    style::{Attribute, Color, ExtendedColor, Rgb, Stylize},
+    utils::{
+        reset_attribute, reset_underline_color, set_attribute, set_bg, set_fg, set_underline_color,
+    },
+};
 
 fn main() -> std::io::Result<()> {
     let mut out = stdout();
+    let capabilities = Capabilities::detect();
 
     set_fg(&mut out, Color::Red)?;
-    write!(out, "Red\n")?;
+    writeln!(out, "Red")?;
 
     set_fg(&mut out, Color::Yellow)?;
-    write!(out, "Yellow\n")?;
+    writeln!(out, "Yellow")?;
 
     set_fg(&mut out, Color::Green)?;
-    write!(out, "Green\n")?;
+    writeln!(out, "Green")?;
+
+    set_fg(&mut out, Color::BrightBlue)?;
+    writeln!(out, "Bright blue")?;
+
+    set_fg(&mut out, ExtendedColor(208))?;
+    writeln!(out, "Orange from the 256 color palette")?;
+
+    set_fg(&mut out, Rgb::new(255, 105, 180))?;
+    set_bg(&mut out, Rgb::new(40, 40, 40))?;
+    writeln!(out, "Truecolor pink on grey")?;
+    set_bg(&mut out, Color::Default)?;
+    set_fg(&mut out, Color::Default)?;
+
+    set_attribute(&mut out, Attribute::Bold)?;
+    writeln!(out, "Bold")?;
+    reset_attribute(&mut out, Attribute::Bold)?;
+
+    set_attribute(&mut out, Attribute::CurlyUnderline)?;
+    set_underline_color(&mut out, &capabilities, Color::Red)?;
+    writeln!(out, "Curly underline")?;
+    reset_underline_color(&mut out, &capabilities)?;
+    reset_attribute(&mut out, Attribute::CurlyUnderline)?;
+
+    writeln!(
+        out,
+        "{}: {}",
+        "Error".red().bold(),
+        "styled with Stylize".on_blue()
+    )?;
 
     // Reset as to not effect other applications after closing
     set_fg(&mut out, Color::Reset)?;
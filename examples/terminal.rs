@@ -0,0 +1,35 @@
+use std::io::Write;
+
+use termip::{
+    command::{MoveCursor, Print},
+    events::KeyCode,
+    geometry::Position,
+    terminal::Terminal,
+};
+
+fn main() -> std::io::Result<()> {
+    let mut terminal = Terminal::builder()
+        .raw_mode(true)
+        .alternate_buffer(true)
+        .cursor_visible(false)
+        .build()?;
+
+    let size = terminal.size()?;
+
+    terminal.queue(MoveCursor(Position::ORIGIN))?;
+    write!(terminal, "{} rows, {} columns", size.rows, size.cols)?;
+    terminal.queue(MoveCursor(Position::new(1, 0)))?;
+    terminal.execute(Print("Press q to quit"))?;
+
+    loop {
+        if terminal
+            .read_batch_blocking(1000)?
+            .pressed(KeyCode::Char('q'))
+        {
+            break;
+        }
+    }
+
+    // Dropping the terminal restores the cursor, main buffer and cooked mode
+    Ok(())
+}
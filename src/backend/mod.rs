@@ -0,0 +1,78 @@
+use std::io::Write;
+
+use crate::{
+    events::EventBatch,
+    geometry::{Position, Size},
+    mode::Mode,
+};
+
+mod test;
+mod tty;
+
+pub use test::TestBackend;
+pub use tty::TtyBackend;
+
+/// The operations an application needs from a terminal. Applications written against this trait
+/// run on a real terminal with `TtyBackend` and in tests with `TestBackend`.
+///
+/// Output written through `Write` is expected to be buffered until `flush` is called.
+pub trait Backend: Write {
+    /// Returns the size of the screen
+    fn size(&self) -> std::io::Result<Size>;
+
+    /// Returns the position of the cursor, or `None` if it is not known
+    fn cursor_position(&mut self) -> std::io::Result<Option<Position>>;
+
+    /// Returns the available events without blocking
+    fn read_batch(&mut self) -> std::io::Result<EventBatch>;
+
+    /// Returns the available events, waiting up to `timeout` milliseconds for the first one
+    fn read_batch_blocking(&mut self, timeout: u32) -> std::io::Result<EventBatch>;
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()>;
+
+    /// Enables a terminal mode. Nothing is flushed
+    fn set_mode(&mut self, mode: Mode) -> std::io::Result<()>;
+
+    /// Disables a terminal mode. Nothing is flushed
+    fn reset_mode(&mut self, mode: Mode) -> std::io::Result<()>;
+}
+
+impl<B> Backend for &mut B
+where
+    B: Backend + ?Sized,
+{
+    fn size(&self) -> std::io::Result<Size> {
+        (**self).size()
+    }
+
+    fn cursor_position(&mut self) -> std::io::Result<Option<Position>> {
+        (**self).cursor_position()
+    }
+
+    fn read_batch(&mut self) -> std::io::Result<EventBatch> {
+        (**self).read_batch()
+    }
+
+    fn read_batch_blocking(&mut self, timeout: u32) -> std::io::Result<EventBatch> {
+        (**self).read_batch_blocking(timeout)
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        (**self).enable_raw_mode()
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        (**self).disable_raw_mode()
+    }
+
+    fn set_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        (**self).set_mode(mode)
+    }
+
+    fn reset_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        (**self).reset_mode(mode)
+    }
+}
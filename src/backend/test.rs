@@ -0,0 +1,146 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    io::Write,
+};
+
+use crate::{
+    events::{Event, EventBatch},
+    geometry::{Position, Size},
+    mode::Mode,
+    utils,
+};
+
+use super::Backend;
+
+/// An in-memory backend for testing applications without a terminal.
+///
+/// Everything written is recorded, split into what has been flushed and what is still pending.
+/// Events are scripted with `push_event` and returned by the next read, and `resize` changes the
+/// size reported to the application and queues an `Event::Resize`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestBackend {
+    size: Size,
+    cursor_position: Option<Position>,
+    events: VecDeque<Event>,
+    flushed: Vec<u8>,
+    pending: Vec<u8>,
+    raw_mode: bool,
+    modes: HashSet<Mode>,
+}
+
+impl TestBackend {
+    pub fn new(size: Size) -> Self {
+        Self {
+            size,
+            cursor_position: None,
+            events: VecDeque::new(),
+            flushed: Vec::new(),
+            pending: Vec::new(),
+            raw_mode: false,
+            modes: HashSet::new(),
+        }
+    }
+
+    /// Queues an event to be returned by the next read
+    pub fn push_event(&mut self, event: Event) {
+        self.events.push_back(event);
+    }
+
+    /// Queues events to be returned by the next read
+    pub fn push_events(&mut self, events: impl IntoIterator<Item = Event>) {
+        self.events.extend(events);
+    }
+
+    /// Changes the size reported by `size` and queues an `Event::Resize` after the events already
+    /// queued
+    pub fn resize(&mut self, size: Size) {
+        self.size = size;
+        self.events.push_back(Event::Resize(size));
+    }
+
+    /// Changes the position reported by `cursor_position`
+    pub fn set_cursor_position(&mut self, position: Option<Position>) {
+        self.cursor_position = position;
+    }
+
+    /// Returns everything that has been flushed
+    pub fn flushed(&self) -> &[u8] {
+        &self.flushed
+    }
+
+    /// Returns everything written since the last flush
+    pub fn pending(&self) -> &[u8] {
+        &self.pending
+    }
+
+    /// Returns and forgets everything that has been flushed, for checking the output of each frame
+    pub fn take_flushed(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.flushed)
+    }
+
+    pub fn is_raw_mode(&self) -> bool {
+        self.raw_mode
+    }
+
+    /// Returns whether a mode has been set and not reset since
+    pub fn is_mode_set(&self, mode: Mode) -> bool {
+        self.modes.contains(&mode)
+    }
+}
+
+impl Backend for TestBackend {
+    fn size(&self) -> std::io::Result<Size> {
+        Ok(self.size)
+    }
+
+    fn cursor_position(&mut self) -> std::io::Result<Option<Position>> {
+        Ok(self.cursor_position)
+    }
+
+    fn read_batch(&mut self) -> std::io::Result<EventBatch> {
+        Ok(EventBatch::from(self.events.drain(..).collect::<Vec<_>>()))
+    }
+
+    /// Returns the queued events immediately, as waiting would never produce new ones
+    fn read_batch_blocking(&mut self, _timeout: u32) -> std::io::Result<EventBatch> {
+        self.read_batch()
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        self.raw_mode = true;
+
+        Ok(())
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        self.raw_mode = false;
+
+        Ok(())
+    }
+
+    fn set_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        self.modes.insert(mode);
+
+        utils::set_mode(&mut self.pending, mode)
+    }
+
+    fn reset_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        self.modes.remove(&mode);
+
+        utils::reset_mode(&mut self.pending, mode)
+    }
+}
+
+impl Write for TestBackend {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.flushed.append(&mut self.pending);
+
+        Ok(())
+    }
+}
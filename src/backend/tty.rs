@@ -0,0 +1,114 @@
+use std::io::{BufWriter, Read, Stdin, Stdout, Write};
+
+use crate::{
+    events::{Event, EventBatch},
+    geometry::{Position, Size},
+    mode::Mode,
+    utils::{self, platform::RawOs},
+};
+
+use super::Backend;
+
+/// A backend on a real terminal, using a buffered output and an input such as stdout and stdin
+pub struct TtyBackend<Output = Stdout, Input = Stdin>
+where
+    Output: Write + RawOs,
+    Input: Read + RawOs,
+{
+    output: BufWriter<Output>,
+    input: Input,
+}
+
+impl TtyBackend {
+    /// Creates a backend on stdout and stdin
+    pub fn new() -> Self {
+        Self::from_parts(std::io::stdout(), std::io::stdin())
+    }
+}
+
+impl Default for TtyBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Output, Input> TtyBackend<Output, Input>
+where
+    Output: Write + RawOs,
+    Input: Read + RawOs,
+{
+    /// Creates a backend on the given handles, for example a file opened from `/dev/tty`
+    pub fn from_parts(output: Output, input: Input) -> Self {
+        Self {
+            output: BufWriter::new(output),
+            input,
+        }
+    }
+
+    pub fn output(&mut self) -> &mut BufWriter<Output> {
+        &mut self.output
+    }
+
+    pub fn input(&mut self) -> &mut Input {
+        &mut self.input
+    }
+
+    /// Reads a single event, see `utils::read_single`
+    pub fn read_single(&mut self) -> std::io::Result<Option<Event>> {
+        utils::read_single(&mut self.input)
+    }
+}
+
+impl<Output, Input> Backend for TtyBackend<Output, Input>
+where
+    Output: Write + RawOs,
+    Input: Read + RawOs,
+{
+    fn size(&self) -> std::io::Result<Size> {
+        utils::get_size(self.output.get_ref())
+    }
+
+    /// Queries the cursor position, see `utils::get_cursor_position`. Pending output is flushed
+    /// first
+    fn cursor_position(&mut self) -> std::io::Result<Option<Position>> {
+        utils::get_cursor_position(&mut self.output, &mut self.input)
+    }
+
+    fn read_batch(&mut self) -> std::io::Result<EventBatch> {
+        utils::read_batch(&mut self.input)
+    }
+
+    fn read_batch_blocking(&mut self, timeout: u32) -> std::io::Result<EventBatch> {
+        utils::read_batch_blocking(&mut self.input, timeout)
+    }
+
+    fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        utils::enable_raw_mode(&mut self.input)
+    }
+
+    fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        utils::disable_raw_mode(&mut self.input)
+    }
+
+    fn set_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        utils::set_mode(&mut self.output, mode)
+    }
+
+    fn reset_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+        utils::reset_mode(&mut self.output, mode)
+    }
+}
+
+impl<Output, Input> Write for TtyBackend<Output, Input>
+where
+    Output: Write + RawOs,
+    Input: Read + RawOs,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
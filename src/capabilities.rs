@@ -0,0 +1,165 @@
+use crate::style::ColorLevel;
+
+/// Values of `TERM` which are known to support underline colors, matched as substrings
+const UNDERLINE_COLOR_TERMS: [&str; 6] = [
+    "kitty",
+    "wezterm",
+    "foot",
+    "ghostty",
+    "alacritty",
+    "contour",
+];
+
+/// Values of `TERM_PROGRAM` which are known to support underline colors
+const UNDERLINE_COLOR_PROGRAMS: [&str; 4] = ["WezTerm", "ghostty", "vscode", "iTerm.app"];
+
+/// Values of `TERM_PROGRAM` which are known to support truecolor without setting `COLORTERM`
+const TRUECOLOR_PROGRAMS: [&str; 3] = ["WezTerm", "ghostty", "iTerm.app"];
+
+/// Terminal features which can not be queried with a control sequence and are instead detected
+/// from the environment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The amount of colors the terminal is able to display
+    pub color_level: ColorLevel,
+
+    /// Whether the terminal understands SGR 58/59 underline colors
+    pub underline_color: bool,
+}
+
+impl Capabilities {
+    /// Detects capabilities from the environment variables of the current process
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    /// Detects capabilities from environment variables provided by `var`. This is useful when the
+    /// environment of another process, for example a remote session, is known
+    pub fn from_env<F>(var: F) -> Self
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        Self {
+            color_level: detect_color_level(&var),
+            underline_color: detect_underline_color(&var),
+        }
+    }
+}
+
+impl Default for Capabilities {
+    /// Assumes the 16 named colors and nothing else
+    fn default() -> Self {
+        Self {
+            color_level: ColorLevel::Ansi16,
+            underline_color: false,
+        }
+    }
+}
+
+fn detect_color_level<F>(var: &F) -> ColorLevel
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+
+    // https://no-color.org
+    if var("NO_COLOR").is_some_and(|value| !value.is_empty()) || term == "dumb" {
+        return ColorLevel::None;
+    }
+
+    let colorterm = var("COLORTERM").unwrap_or_default();
+
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        return ColorLevel::TrueColor;
+    }
+
+    if TRUECOLOR_PROGRAMS.contains(&term_program.as_str()) {
+        return ColorLevel::TrueColor;
+    }
+
+    if term.contains("256color") {
+        return ColorLevel::Ansi256;
+    }
+
+    ColorLevel::Ansi16
+}
+
+fn detect_underline_color<F>(var: &F) -> bool
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let term = var("TERM").unwrap_or_default();
+    let term_program = var("TERM_PROGRAM").unwrap_or_default();
+
+    if UNDERLINE_COLOR_TERMS.iter().any(|name| term.contains(name)) {
+        return true;
+    }
+
+    if UNDERLINE_COLOR_PROGRAMS.contains(&term_program.as_str()) {
+        return true;
+    }
+
+    if var("KITTY_WINDOW_ID").is_some() {
+        return true;
+    }
+
+    // VTE based terminals encode their version as a number, 0.51.2 introduced underline colors
+    var("VTE_VERSION")
+        .and_then(|version| version.parse::<u32>().ok())
+        .is_some_and(|version| version >= 5102)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::from_env(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn color_level() {
+        assert_eq!(detect(&[]).color_level, ColorLevel::Ansi16);
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color")]).color_level,
+            ColorLevel::Ansi256
+        );
+        assert_eq!(
+            detect(&[("TERM", "xterm-256color"), ("COLORTERM", "truecolor")]).color_level,
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            detect(&[("COLORTERM", "24bit")]).color_level,
+            ColorLevel::TrueColor
+        );
+        assert_eq!(
+            detect(&[("TERM_PROGRAM", "WezTerm")]).color_level,
+            ColorLevel::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "dumb")]).color_level, ColorLevel::None);
+    }
+
+    #[test]
+    fn no_color_disables_colors() {
+        let vars = [("NO_COLOR", "1"), ("COLORTERM", "truecolor")];
+        assert_eq!(detect(&vars).color_level, ColorLevel::None);
+
+        // An empty value does not count, see https://no-color.org
+        let vars = [("NO_COLOR", ""), ("COLORTERM", "truecolor")];
+        assert_eq!(detect(&vars).color_level, ColorLevel::TrueColor);
+    }
+
+    #[test]
+    fn underline_color() {
+        assert!(!detect(&[("TERM", "xterm-256color")]).underline_color);
+        assert!(detect(&[("TERM", "xterm-kitty")]).underline_color);
+        assert!(detect(&[("TERM_PROGRAM", "vscode")]).underline_color);
+        assert!(detect(&[("VTE_VERSION", "6003")]).underline_color);
+        assert!(!detect(&[("VTE_VERSION", "5000")]).underline_color);
+    }
+}
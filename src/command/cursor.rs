@@ -0,0 +1,160 @@
+use std::io::Write;
+
+use crate::{
+    geometry::Position,
+    style::Rgb,
+    utils::{self, CursorShape},
+};
+
+use super::Command;
+
+/// Moves the cursor to a 0-based position, see `utils::move_cursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursor(pub Position);
+
+impl Command for MoveCursor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor up by a number of lines, see `utils::move_cursor_up`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorUp(pub u16);
+
+impl Command for MoveCursorUp {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_up(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor down by a number of lines, see `utils::move_cursor_down`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorDown(pub u16);
+
+impl Command for MoveCursorDown {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_down(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor right by a number of columns, see `utils::move_cursor_right`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorRight(pub u16);
+
+impl Command for MoveCursorRight {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_right(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor left by a number of columns, see `utils::move_cursor_left`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorLeft(pub u16);
+
+impl Command for MoveCursorLeft {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_left(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor to a 0-based column, see `utils::move_cursor_to_column`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorToColumn(pub u16);
+
+impl Command for MoveCursorToColumn {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_to_column(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor to the start of a following line, see `utils::move_cursor_to_next_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorToNextLine(pub u16);
+
+impl Command for MoveCursorToNextLine {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_to_next_line(&mut output, self.0)
+    }
+}
+
+/// Moves the cursor to the start of a preceding line, see
+/// `utils::move_cursor_to_previous_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MoveCursorToPreviousLine(pub u16);
+
+impl Command for MoveCursorToPreviousLine {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::move_cursor_to_previous_line(&mut output, self.0)
+    }
+}
+
+/// Saves the cursor with DECSC, see `utils::save_cursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct SaveCursor;
+
+impl Command for SaveCursor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::save_cursor(&mut output)
+    }
+}
+
+/// Restores the cursor with DECRC, see `utils::restore_cursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RestoreCursor;
+
+impl Command for RestoreCursor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::restore_cursor(&mut output)
+    }
+}
+
+/// Hides the cursor, see `utils::hide_cursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct HideCursor;
+
+impl Command for HideCursor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::hide_cursor(&mut output)
+    }
+}
+
+/// Shows the cursor, see `utils::show_cursor`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ShowCursor;
+
+impl Command for ShowCursor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::show_cursor(&mut output)
+    }
+}
+
+/// Sets the shape of the cursor, see `utils::set_cursor_shape`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetCursorShape(pub CursorShape);
+
+impl Command for SetCursorShape {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_cursor_shape(&mut output, self.0)
+    }
+}
+
+/// Sets the color of the cursor, see `utils::set_cursor_color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetCursorColor(pub Rgb);
+
+impl Command for SetCursorColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_cursor_color(&mut output, self.0)
+    }
+}
+
+/// Resets the color of the cursor, see `utils::reset_cursor_color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResetCursorColor;
+
+impl Command for ResetCursorColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_cursor_color(&mut output)
+    }
+}
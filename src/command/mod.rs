@@ -0,0 +1,363 @@
+use std::{fmt, io::Write};
+
+mod cursor;
+mod mode;
+mod screen;
+mod style;
+
+pub use cursor::{
+    HideCursor, MoveCursor, MoveCursorDown, MoveCursorLeft, MoveCursorRight, MoveCursorToColumn,
+    MoveCursorToNextLine, MoveCursorToPreviousLine, MoveCursorUp, ResetCursorColor, RestoreCursor,
+    SaveCursor, SetCursorColor, SetCursorShape, ShowCursor,
+};
+pub use mode::{ResetMode, SetMode};
+pub use screen::{
+    BeginSynchronizedUpdate, ClearScrollback, DeleteChars, DeleteLines, EndSynchronizedUpdate,
+    EnterAlternateBuffer, EraseAbove, EraseBelow, EraseChars, EraseEntireScreen, EraseLine,
+    EraseToEndOfLine, EraseToStartOfLine, InsertChars, InsertLines, LeaveAlternateBuffer,
+    ResetDynamicColor, ResetPalette, ResetPaletteColor, ResetScrollRegion, ScrollDown, ScrollUp,
+    SetDynamicColor, SetPaletteColor, SetScrollRegion,
+};
+pub use style::{
+    Print, ResetAttribute, ResetStyle, ResetUnderlineColor, SetAttribute, SetAttributes,
+    SetBackground, SetForeground, SetStyle, SetUnderlineColor,
+};
+
+/// An operation on the terminal which is performed by writing escape sequences. Commands are
+/// plain values, so they can be collected, passed around and inspected before being written.
+pub trait Command {
+    /// Writes the escape sequences of the command
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()>;
+
+    /// Writes the escape sequences of the command to a `fmt::Write`, such as a `String`
+    fn write_ansi_fmt(&self, output: &mut dyn fmt::Write) -> fmt::Result {
+        self.write_ansi(&mut FmtWriter { inner: output })
+            .map_err(|_| fmt::Error)
+    }
+
+    /// Returns the escape sequences of the command
+    fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+
+        // Writing to a string only fails if a command fails itself
+        let _ = self.write_ansi_fmt(&mut ansi);
+
+        ansi
+    }
+}
+
+impl<T> Command for &T
+where
+    T: Command + ?Sized,
+{
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        (**self).write_ansi(output)
+    }
+}
+
+impl<T> Command for Box<T>
+where
+    T: Command + ?Sized,
+{
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        (**self).write_ansi(output)
+    }
+}
+
+impl<T> Command for [T]
+where
+    T: Command,
+{
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        for command in self {
+            command.write_ansi(output)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T> Command for Vec<T>
+where
+    T: Command,
+{
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        self.as_slice().write_ansi(output)
+    }
+}
+
+/// Adapts a `fmt::Write` for use with commands. Commands only write complete UTF-8 strings
+struct FmtWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+}
+
+impl Write for FmtWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let string = std::str::from_utf8(buf)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+        self.inner
+            .write_str(string)
+            .map_err(|_| std::io::Error::other("formatter error"))?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A list of commands of any type, which is itself a command writing each of them in order
+#[derive(Default)]
+pub struct Batch {
+    commands: Vec<Box<dyn Command>>,
+}
+
+impl Batch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a command to the end of the batch
+    pub fn push(&mut self, command: impl Command + 'static) {
+        self.commands.push(Box::new(command));
+    }
+
+    /// Adds a command to the end of the batch, for chaining
+    pub fn with(mut self, command: impl Command + 'static) -> Self {
+        self.push(command);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Command> {
+        self.commands.iter().map(|command| command.as_ref())
+    }
+
+    pub fn into_inner(self) -> Vec<Box<dyn Command>> {
+        self.commands
+    }
+}
+
+impl Command for Batch {
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        self.commands.write_ansi(output)
+    }
+}
+
+impl fmt::Debug for Batch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.commands.iter().map(|command| command.to_ansi()))
+            .finish()
+    }
+}
+
+impl From<Vec<Box<dyn Command>>> for Batch {
+    fn from(commands: Vec<Box<dyn Command>>) -> Self {
+        Self { commands }
+    }
+}
+
+impl FromIterator<Box<dyn Command>> for Batch {
+    fn from_iter<I: IntoIterator<Item = Box<dyn Command>>>(iter: I) -> Self {
+        Self {
+            commands: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<Box<dyn Command>> for Batch {
+    fn extend<I: IntoIterator<Item = Box<dyn Command>>>(&mut self, iter: I) {
+        self.commands.extend(iter);
+    }
+}
+
+/// This function writes a command without flushing, so several commands can be sent at once
+pub fn queue<Output>(output: &mut Output, command: impl Command) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    command.write_ansi(output)
+}
+
+/// This function writes a command and flushes the output
+pub fn execute<Output>(output: &mut Output, command: impl Command) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    command.write_ansi(output)?;
+
+    output.flush()
+}
+
+/// Writes any number of commands to an output without flushing, evaluating to
+/// `std::io::Result<()>`. Writing stops at the first error.
+///
+/// ```no_run
+/// use termip::{command::{MoveCursor, Print}, geometry::Position, queue};
+///
+/// let mut out = std::io::stdout();
+///
+/// queue!(out, MoveCursor(Position::new(2, 4)), Print("hello")).unwrap();
+/// ```
+#[macro_export]
+macro_rules! queue {
+    ($output:expr $(, $command:expr)* $(,)?) => {
+        Ok::<(), ::std::io::Error>(())
+            $(.and_then(|()| $crate::command::queue(&mut $output, $command)))*
+    };
+}
+
+/// Writes any number of commands to an output like `queue!` and flushes it afterwards
+#[macro_export]
+macro_rules! execute {
+    ($output:expr $(, $command:expr)* $(,)?) => {
+        $crate::queue!($output $(, $command)*)
+            .and_then(|()| ::std::io::Write::flush(&mut $output))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        events::DynamicColor,
+        geometry::Position,
+        mode::Mode,
+        style::{Attribute, Attributes, Color, Rgb, Style},
+        utils::{self, CursorShape},
+    };
+
+    /// Returns what a function writes
+    fn written(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    macro_rules! assert_same_output {
+        ($($command:expr => |$output:ident| $write:expr),* $(,)?) => {
+            $(
+                assert_eq!($command.to_ansi(), written(|$output| $write), "{:?}", $command);
+            )*
+        };
+    }
+
+    #[test]
+    fn commands_match_utils() {
+        let position = Position::new(3, 7);
+        let rgb = Rgb::new(1, 2, 3);
+        let attributes = Attributes::from(Attribute::Bold).with(Attribute::Italic);
+
+        assert_same_output! {
+            MoveCursor(position) => |o| utils::move_cursor(o, position),
+            MoveCursorUp(2) => |o| utils::move_cursor_up(o, 2),
+            MoveCursorDown(0) => |o| utils::move_cursor_down(o, 0),
+            MoveCursorRight(4) => |o| utils::move_cursor_right(o, 4),
+            MoveCursorLeft(1) => |o| utils::move_cursor_left(o, 1),
+            MoveCursorToColumn(9) => |o| utils::move_cursor_to_column(o, 9),
+            MoveCursorToNextLine(2) => |o| utils::move_cursor_to_next_line(o, 2),
+            MoveCursorToPreviousLine(2) => |o| utils::move_cursor_to_previous_line(o, 2),
+            SaveCursor => |o| utils::save_cursor(o),
+            RestoreCursor => |o| utils::restore_cursor(o),
+            HideCursor => |o| utils::hide_cursor(o),
+            ShowCursor => |o| utils::show_cursor(o),
+            SetCursorShape(CursorShape::SteadyBar) =>
+                |o| utils::set_cursor_shape(o, CursorShape::SteadyBar),
+            SetCursorColor(rgb) => |o| utils::set_cursor_color(o, rgb),
+            ResetCursorColor => |o| utils::reset_cursor_color(o),
+
+            SetMode(Mode::ORIGIN) => |o| utils::set_mode(o, Mode::ORIGIN),
+            ResetMode(Mode::INSERT) => |o| utils::reset_mode(o, Mode::INSERT),
+
+            EnterAlternateBuffer => |o| utils::enter_alternate_buffer(o),
+            LeaveAlternateBuffer => |o| utils::leave_alternate_buffer(o),
+            BeginSynchronizedUpdate => |o| utils::begin_synchronized_update(o),
+            EndSynchronizedUpdate => |o| utils::end_synchronized_update(o),
+            EraseEntireScreen => |o| utils::erase_entire_screen(o),
+            EraseLine => |o| utils::erase_line(o),
+            EraseToEndOfLine => |o| utils::erase_to_end_of_line(o),
+            EraseToStartOfLine => |o| utils::erase_to_start_of_line(o),
+            EraseBelow => |o| utils::erase_below(o),
+            EraseAbove => |o| utils::erase_above(o),
+            ClearScrollback => |o| utils::clear_scrollback(o),
+            SetScrollRegion { top: 2, bottom: 10 } => |o| utils::set_scroll_region(o, 2, 10),
+            ResetScrollRegion => |o| utils::reset_scroll_region(o),
+            ScrollUp(3) => |o| utils::scroll_up(o, 3),
+            ScrollDown(0) => |o| utils::scroll_down(o, 0),
+            InsertLines(1) => |o| utils::insert_lines(o, 1),
+            DeleteLines(2) => |o| utils::delete_lines(o, 2),
+            InsertChars(3) => |o| utils::insert_chars(o, 3),
+            DeleteChars(4) => |o| utils::delete_chars(o, 4),
+            EraseChars(5) => |o| utils::erase_chars(o, 5),
+            SetPaletteColor { index: 7, rgb } => |o| utils::set_palette_color(o, 7, rgb),
+            ResetPaletteColor(7) => |o| utils::reset_palette_color(o, 7),
+            ResetPalette => |o| utils::reset_palette(o),
+            SetDynamicColor { color: DynamicColor::Background, rgb } =>
+                |o| utils::set_dynamic_color(o, DynamicColor::Background, rgb),
+            ResetDynamicColor(DynamicColor::Cursor) =>
+                |o| utils::reset_dynamic_color(o, DynamicColor::Cursor),
+
+            SetForeground(Color::Extended(200)) => |o| utils::set_fg(o, Color::Extended(200)),
+            SetBackground(Color::Rgb(rgb)) => |o| utils::set_bg(o, Color::Rgb(rgb)),
+            SetAttribute(Attribute::CurlyUnderline) =>
+                |o| utils::set_attribute(o, Attribute::CurlyUnderline),
+            ResetAttribute(Attribute::Dim) => |o| utils::reset_attribute(o, Attribute::Dim),
+            SetAttributes(attributes) => |o| utils::set_attributes(o, attributes),
+        }
+    }
+
+    #[test]
+    fn style_commands() {
+        let style = Style::new()
+            .with_fg(Color::Red)
+            .with_attribute(Attribute::Bold);
+
+        assert_eq!(SetStyle(style).to_ansi(), style.sequence());
+        assert_eq!(ResetStyle.to_ansi(), "\x1b[0m");
+        assert_eq!(SetUnderlineColor(Color::Red).to_ansi(), "\x1b[58:5:1m");
+        assert_eq!(ResetUnderlineColor.to_ansi(), "\x1b[59m");
+        assert_eq!(Print("héllo").to_ansi(), "héllo");
+    }
+
+    #[test]
+    fn batches_keep_order() {
+        let mut batch = Batch::new()
+            .with(MoveCursor(Position::ORIGIN))
+            .with(Print("a"))
+            .with(ScrollUp(1));
+        batch.push(Print('b'));
+        batch.extend([Box::new(HideCursor) as Box<dyn Command>]);
+
+        assert_eq!(batch.len(), 5);
+        assert_eq!(batch.to_ansi(), "\x1b[1;1Ha\x1b[1Sb\x1b[?25l");
+        assert_eq!(
+            format!("{:?}", batch),
+            r#"["\u{1b}[1;1H", "a", "\u{1b}[1S", "b", "\u{1b}[?25l"]"#
+        );
+
+        let mut output = Vec::new();
+        queue(&mut output, &batch).unwrap();
+        crate::queue!(output, Print("c"), [ShowCursor, ShowCursor].as_slice()).unwrap();
+
+        assert_eq!(output, b"\x1b[1;1Ha\x1b[1Sb\x1b[?25lc\x1b[?25h\x1b[?25h");
+
+        batch.clear();
+        assert!(batch.is_empty());
+        assert_eq!(batch.to_ansi(), "");
+    }
+}
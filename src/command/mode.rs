@@ -0,0 +1,25 @@
+use std::io::Write;
+
+use crate::{mode::Mode, utils};
+
+use super::Command;
+
+/// Enables a terminal mode, see `utils::set_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetMode(pub Mode);
+
+impl Command for SetMode {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_mode(&mut output, self.0)
+    }
+}
+
+/// Disables a terminal mode, see `utils::reset_mode`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResetMode(pub Mode);
+
+impl Command for ResetMode {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_mode(&mut output, self.0)
+    }
+}
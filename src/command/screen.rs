@@ -0,0 +1,268 @@
+use std::io::Write;
+
+use crate::{events::DynamicColor, style::Rgb, utils};
+
+use super::Command;
+
+/// Switches to the alternate screen buffer, see `utils::enter_alternate_buffer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EnterAlternateBuffer;
+
+impl Command for EnterAlternateBuffer {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::enter_alternate_buffer(&mut output)
+    }
+}
+
+/// Switches back to the main screen buffer, see `utils::leave_alternate_buffer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct LeaveAlternateBuffer;
+
+impl Command for LeaveAlternateBuffer {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::leave_alternate_buffer(&mut output)
+    }
+}
+
+/// Holds rendering until the update ends, see `utils::begin_synchronized_update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct BeginSynchronizedUpdate;
+
+impl Command for BeginSynchronizedUpdate {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::begin_synchronized_update(&mut output)
+    }
+}
+
+/// Displays everything written since the update began, see
+/// `utils::end_synchronized_update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EndSynchronizedUpdate;
+
+impl Command for EndSynchronizedUpdate {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::end_synchronized_update(&mut output)
+    }
+}
+
+/// Erases the whole screen, see `utils::erase_entire_screen`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseEntireScreen;
+
+impl Command for EraseEntireScreen {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_entire_screen(&mut output)
+    }
+}
+
+/// Erases the line of the cursor, see `utils::erase_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseLine;
+
+impl Command for EraseLine {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_line(&mut output)
+    }
+}
+
+/// Erases from the cursor to the end of the line, see `utils::erase_to_end_of_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseToEndOfLine;
+
+impl Command for EraseToEndOfLine {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_to_end_of_line(&mut output)
+    }
+}
+
+/// Erases from the start of the line to the cursor, see `utils::erase_to_start_of_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseToStartOfLine;
+
+impl Command for EraseToStartOfLine {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_to_start_of_line(&mut output)
+    }
+}
+
+/// Erases from the cursor to the end of the screen, see `utils::erase_below`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseBelow;
+
+impl Command for EraseBelow {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_below(&mut output)
+    }
+}
+
+/// Erases from the start of the screen to the cursor, see `utils::erase_above`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct EraseAbove;
+
+impl Command for EraseAbove {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_above(&mut output)
+    }
+}
+
+/// Deletes the scrollback, see `utils::clear_scrollback`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ClearScrollback;
+
+impl Command for ClearScrollback {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::clear_scrollback(&mut output)
+    }
+}
+
+/// Limits scrolling to the 0-based rows from `top` to `bottom`, see `utils::set_scroll_region`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetScrollRegion {
+    pub top: u16,
+    pub bottom: u16,
+}
+
+impl Command for SetScrollRegion {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_scroll_region(&mut output, self.top, self.bottom)
+    }
+}
+
+/// Lets the entire screen scroll again, see `utils::reset_scroll_region`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResetScrollRegion;
+
+impl Command for ResetScrollRegion {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_scroll_region(&mut output)
+    }
+}
+
+/// Scrolls the scroll region up by a number of lines, see `utils::scroll_up`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScrollUp(pub u16);
+
+impl Command for ScrollUp {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::scroll_up(&mut output, self.0)
+    }
+}
+
+/// Scrolls the scroll region down by a number of lines, see `utils::scroll_down`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScrollDown(pub u16);
+
+impl Command for ScrollDown {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::scroll_down(&mut output, self.0)
+    }
+}
+
+/// Inserts blank lines at the cursor, see `utils::insert_lines`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InsertLines(pub u16);
+
+impl Command for InsertLines {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::insert_lines(&mut output, self.0)
+    }
+}
+
+/// Deletes lines at the cursor, see `utils::delete_lines`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeleteLines(pub u16);
+
+impl Command for DeleteLines {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::delete_lines(&mut output, self.0)
+    }
+}
+
+/// Inserts blank characters at the cursor, see `utils::insert_chars`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InsertChars(pub u16);
+
+impl Command for InsertChars {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::insert_chars(&mut output, self.0)
+    }
+}
+
+/// Deletes characters at the cursor, see `utils::delete_chars`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeleteChars(pub u16);
+
+impl Command for DeleteChars {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::delete_chars(&mut output, self.0)
+    }
+}
+
+/// Erases characters at the cursor, see `utils::erase_chars`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EraseChars(pub u16);
+
+impl Command for EraseChars {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::erase_chars(&mut output, self.0)
+    }
+}
+
+/// Changes an entry of the 256 color palette, see `utils::set_palette_color`. The change is not
+/// recorded, use `Terminal::set_palette_color` to have it undone when the terminal is restored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetPaletteColor {
+    pub index: u8,
+    pub rgb: Rgb,
+}
+
+impl Command for SetPaletteColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_palette_color(&mut output, self.index, self.rgb)
+    }
+}
+
+/// Resets an entry of the 256 color palette, see `utils::reset_palette_color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResetPaletteColor(pub u8);
+
+impl Command for ResetPaletteColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_palette_color(&mut output, self.0)
+    }
+}
+
+/// Resets the entire palette, see `utils::reset_palette`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResetPalette;
+
+impl Command for ResetPalette {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_palette(&mut output)
+    }
+}
+
+/// Sets the default foreground, default background or cursor color, see
+/// `utils::set_dynamic_color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetDynamicColor {
+    pub color: DynamicColor,
+    pub rgb: Rgb,
+}
+
+impl Command for SetDynamicColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_dynamic_color(&mut output, self.color, self.rgb)
+    }
+}
+
+/// Resets the default foreground, default background or cursor color, see
+/// `utils::reset_dynamic_color`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResetDynamicColor(pub DynamicColor);
+
+impl Command for ResetDynamicColor {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_dynamic_color(&mut output, self.0)
+    }
+}
@@ -0,0 +1,118 @@
+use std::{fmt::Display, io::Write};
+
+use crate::{
+    style::{Attribute, Attributes, Color, ColorCode, Style, UnderlineCode},
+    utils,
+};
+
+use super::Command;
+
+/// Sets the foreground color, see `utils::set_fg`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetForeground(pub Color);
+
+impl Command for SetForeground {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_fg(&mut output, self.0)
+    }
+}
+
+/// Sets the background color, see `utils::set_bg`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetBackground(pub Color);
+
+impl Command for SetBackground {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_bg(&mut output, self.0)
+    }
+}
+
+/// Sets the underline color. Unlike `utils::set_underline_color` this is written regardless of
+/// capabilities, like the underline color of `SetStyle`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetUnderlineColor(pub Color);
+
+impl Command for SetUnderlineColor {
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        write!(
+            output,
+            "\x1b[{}m",
+            ColorCode::<UnderlineCode>::code(&self.0)
+        )
+    }
+}
+
+/// Resets the underline color to follow the foreground color. Unlike
+/// `utils::reset_underline_color` this is written regardless of capabilities
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResetUnderlineColor;
+
+impl Command for ResetUnderlineColor {
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        write!(output, "\x1b[59m")
+    }
+}
+
+/// Enables a text attribute, see `utils::set_attribute`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetAttribute(pub Attribute);
+
+impl Command for SetAttribute {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_attribute(&mut output, self.0)
+    }
+}
+
+/// Disables a text attribute, see `utils::reset_attribute`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResetAttribute(pub Attribute);
+
+impl Command for ResetAttribute {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::reset_attribute(&mut output, self.0)
+    }
+}
+
+/// Enables a set of text attributes, see `utils::set_attributes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetAttributes(pub Attributes);
+
+impl Command for SetAttributes {
+    fn write_ansi(&self, mut output: &mut dyn Write) -> std::io::Result<()> {
+        utils::set_attributes(&mut output, self.0)
+    }
+}
+
+/// Applies a style on top of the current pen, see `Style::sequence`. Use `ResetStyle` first to
+/// replace the pen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SetStyle(pub Style);
+
+impl Command for SetStyle {
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        output.write_all(self.0.sequence().as_bytes())
+    }
+}
+
+/// Resets all colors and attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResetStyle;
+
+impl Command for ResetStyle {
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        write!(output, "\x1b[0m")
+    }
+}
+
+/// Prints a value at the cursor using the current pen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Print<T>(pub T);
+
+impl<T> Command for Print<T>
+where
+    T: Display,
+{
+    fn write_ansi(&self, output: &mut dyn Write) -> std::io::Result<()> {
+        write!(output, "{}", self.0)
+    }
+}
@@ -0,0 +1,987 @@
+use std::{collections::HashSet, io::Write};
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::{
+    geometry::{Position, Size},
+    mode::Mode,
+    render::{Buffer, Cell},
+    style::{apply_sgr, Style},
+    utils::CursorShape,
+};
+
+mod parser;
+
+use parser::{Action, Parser};
+
+/// The default number of lines kept in the scrollback
+const SCROLLBACK_LIMIT: usize = 1000;
+
+/// Modes which are set when the emulator is created or reset
+const DEFAULT_MODES: [Mode; 2] = [Mode::AUTO_WRAP, Mode::CURSOR_VISIBLE];
+
+/// The state saved by DECSC and restored by DECRC
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct SavedCursor {
+    position: Position,
+    pen: Style,
+    origin: bool,
+}
+
+/// A headless terminal which interprets output into a grid of styled cells, so tests can check
+/// what a user would see instead of comparing escape sequences.
+///
+/// A subset of VT100/xterm is understood, covering everything termip writes: printing with
+/// autowrap and wide characters, cursor movement, SGR, erasing, inserting and deleting, scroll
+/// regions, the alternate buffer, DECSC/DECRC, DECSCUSR and mode changes. Unknown sequences are
+/// ignored. Lines scrolled off the top of the main screen are kept in the scrollback.
+///
+/// Replies to DSR and DECRQM requests are collected and can be fed back to an application with
+/// `take_replies`.
+#[derive(Debug, Clone)]
+pub struct Emulator {
+    parser: Parser,
+    main: Buffer,
+    alternate: Buffer,
+    alternate_active: bool,
+    scrollback: Vec<Vec<Cell>>,
+    scrollback_limit: usize,
+    cursor: Position,
+    pending_wrap: bool,
+    saved: Option<SavedCursor>,
+    pen: Style,
+    last_printed: Option<char>,
+    scroll_top: u16,
+    scroll_bottom: u16,
+    modes: HashSet<Mode>,
+    cursor_shape: CursorShape,
+    replies: Vec<u8>,
+}
+
+impl Emulator {
+    pub fn new(size: Size) -> Self {
+        Self {
+            parser: Parser::new(),
+            main: Buffer::new(size),
+            alternate: Buffer::new(size),
+            alternate_active: false,
+            scrollback: Vec::new(),
+            scrollback_limit: SCROLLBACK_LIMIT,
+            cursor: Position::ORIGIN,
+            pending_wrap: false,
+            saved: None,
+            pen: Style::default(),
+            last_printed: None,
+            scroll_top: 0,
+            scroll_bottom: size.rows.saturating_sub(1),
+            modes: HashSet::from(DEFAULT_MODES),
+            cursor_shape: CursorShape::Default,
+            replies: Vec::new(),
+        }
+    }
+
+    /// Interprets terminal output. Sequences split between calls are completed by later calls
+    pub fn process(&mut self, bytes: &[u8]) {
+        for action in self.parser.advance(bytes) {
+            match action {
+                Action::Print(character) => self.print(character),
+                Action::Execute(byte) => self.execute(byte),
+                Action::Csi {
+                    private,
+                    params,
+                    intermediates,
+                    action,
+                } => self.csi(private, &params, &intermediates, action),
+                Action::Esc {
+                    intermediates,
+                    action,
+                } => self.esc(&intermediates, action),
+                Action::String => {}
+            }
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.main.size()
+    }
+
+    /// Changes the size of both screens, keeping their contents where they fit. The scroll region
+    /// is reset
+    pub fn resize(&mut self, size: Size) {
+        self.main.resize(size);
+        self.alternate.resize(size);
+
+        self.scroll_top = 0;
+        self.scroll_bottom = size.rows.saturating_sub(1);
+        self.cursor = Position::new(
+            self.cursor.row.min(size.rows.saturating_sub(1)),
+            self.cursor.col.min(size.cols.saturating_sub(1)),
+        );
+        self.pending_wrap = false;
+    }
+
+    /// Returns the screen that is currently displayed
+    pub fn screen(&self) -> &Buffer {
+        if self.alternate_active {
+            &self.alternate
+        } else {
+            &self.main
+        }
+    }
+
+    pub fn cell(&self, position: Position) -> Option<&Cell> {
+        self.screen().get(position)
+    }
+
+    /// Returns the text of a row without styles or trailing spaces
+    pub fn row_text(&self, row: u16) -> String {
+        row_text(self.screen().row(row))
+    }
+
+    /// Returns the text of the screen without styles, one line per row with trailing spaces
+    /// removed
+    pub fn contents(&self) -> String {
+        (0..self.screen().height())
+            .map(|row| self.row_text(row))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Returns the lines scrolled off the top of the main screen, oldest first
+    pub fn scrollback(&self) -> &[Vec<Cell>] {
+        &self.scrollback
+    }
+
+    /// Returns the text of the scrollback, one line per row with trailing spaces removed
+    pub fn scrollback_text(&self) -> Vec<String> {
+        self.scrollback.iter().map(|row| row_text(row)).collect()
+    }
+
+    /// Changes the number of lines kept in the scrollback, dropping the oldest lines if needed
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.scrollback_limit = limit;
+        self.trim_scrollback();
+    }
+
+    pub fn cursor(&self) -> Position {
+        self.cursor
+    }
+
+    pub fn cursor_shape(&self) -> CursorShape {
+        self.cursor_shape
+    }
+
+    pub fn is_cursor_visible(&self) -> bool {
+        self.is_mode_set(Mode::CURSOR_VISIBLE)
+    }
+
+    /// Returns the style applied to printed characters
+    pub fn pen(&self) -> Style {
+        self.pen
+    }
+
+    /// Returns the 0-based rows from the top to the bottom of the scroll region (inclusive)
+    pub fn scroll_region(&self) -> (u16, u16) {
+        (self.scroll_top, self.scroll_bottom)
+    }
+
+    pub fn is_alternate_buffer(&self) -> bool {
+        self.alternate_active
+    }
+
+    /// Returns whether a mode is set. Auto wrap and cursor visibility are set initially
+    pub fn is_mode_set(&self, mode: Mode) -> bool {
+        self.modes.contains(&mode)
+    }
+
+    /// Returns and forgets the replies to requests, such as cursor position reports
+    pub fn take_replies(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.replies)
+    }
+
+    /// Resets everything except the size and scrollback, like RIS
+    pub fn reset(&mut self) {
+        let scrollback = std::mem::take(&mut self.scrollback);
+        let limit = self.scrollback_limit;
+
+        *self = Self::new(self.size());
+
+        self.scrollback = scrollback;
+        self.scrollback_limit = limit;
+    }
+
+    fn screen_mut(&mut self) -> &mut Buffer {
+        if self.alternate_active {
+            &mut self.alternate
+        } else {
+            &mut self.main
+        }
+    }
+
+    fn rows(&self) -> u16 {
+        self.main.height()
+    }
+
+    fn cols(&self) -> u16 {
+        self.main.width()
+    }
+
+    /// Whether the screen has no cells, in which case nothing can be drawn or moved
+    fn is_empty(&self) -> bool {
+        self.rows() == 0 || self.cols() == 0
+    }
+
+    /// Returns an erased cell, which keeps the background of the pen
+    fn blank(&self) -> Cell {
+        Cell::blank(Style {
+            bg: self.pen.bg,
+            ..Style::default()
+        })
+    }
+
+    fn print(&mut self, character: char) {
+        let width = character.width().unwrap_or(0) as u16;
+
+        if self.is_empty() {
+            return;
+        }
+
+        if width == 0 {
+            self.combine(character);
+            return;
+        }
+
+        let autowrap = self.is_mode_set(Mode::AUTO_WRAP);
+
+        if self.pending_wrap && autowrap {
+            self.cursor.col = 0;
+            self.line_feed();
+        }
+
+        self.pending_wrap = false;
+
+        // A wide character that does not fit wraps early, or is dropped without autowrap
+        if self.cursor.col + width > self.cols() {
+            if !autowrap || width > self.cols() {
+                return;
+            }
+
+            self.cursor.col = 0;
+            self.line_feed();
+        }
+
+        if self.is_mode_set(Mode::INSERT) {
+            self.insert_chars(width);
+        }
+
+        let cell = Cell::new(character.encode_utf8(&mut [0; 4]), self.pen);
+        let Position { row, col } = self.cursor;
+
+        self.screen_mut().set(Position::new(row, col), cell);
+        self.last_printed = Some(character);
+
+        if col + width >= self.cols() {
+            self.cursor.col = self.cols() - 1;
+            self.pending_wrap = autowrap;
+        } else {
+            self.cursor.col = col + width;
+        }
+    }
+
+    /// Appends a zero width character, such as a combining accent, to the last printed cell
+    fn combine(&mut self, character: char) {
+        let Position { row, mut col } = self.cursor;
+
+        if !self.pending_wrap {
+            let Some(previous) = col.checked_sub(1) else {
+                return;
+            };
+
+            col = previous;
+        }
+
+        let Some(cell) = self.screen().get(Position::new(row, col)) else {
+            return;
+        };
+
+        // The left half of a wide character holds its symbol
+        let col = if cell.is_continuation() {
+            col.saturating_sub(1)
+        } else {
+            col
+        };
+
+        let Some(cell) = self.screen().get(Position::new(row, col)) else {
+            return;
+        };
+
+        let mut symbol = cell.symbol().to_string();
+        symbol.push(character);
+
+        let cell = Cell::new(&symbol, *cell.style());
+        self.screen_mut().set(Position::new(row, col), cell);
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            b'\x08' => {
+                self.cursor.col = self.cursor.col.saturating_sub(1);
+                self.pending_wrap = false;
+            }
+            b'\t' => {
+                let next = (self.cursor.col / 8 + 1) * 8;
+
+                self.cursor.col = next.min(self.cols().saturating_sub(1));
+                self.pending_wrap = false;
+            }
+            b'\n' | b'\x0b' | b'\x0c' => {
+                if self.is_mode_set(Mode::AUTOMATIC_NEWLINE) {
+                    self.cursor.col = 0;
+                }
+
+                self.line_feed();
+            }
+            b'\r' => {
+                self.cursor.col = 0;
+                self.pending_wrap = false;
+            }
+            _ => {}
+        }
+    }
+
+    fn esc(&mut self, intermediates: &[u8], action: u8) {
+        if !intermediates.is_empty() {
+            // Character set selection and similar sequences do not change the screen
+            return;
+        }
+
+        match action {
+            b'7' => self.save_cursor(),
+            b'8' => self.restore_cursor(),
+            b'D' => self.line_feed(),
+            b'E' => {
+                self.cursor.col = 0;
+                self.line_feed();
+            }
+            b'M' => self.reverse_index(),
+            b'c' => self.reset(),
+            _ => {}
+        }
+    }
+
+    fn csi(&mut self, private: Option<u8>, params: &str, intermediates: &[u8], action: u8) {
+        let values: Vec<u16> = params
+            .split(';')
+            .map(|i| i.split(':').next().unwrap_or("").parse().unwrap_or(0))
+            .collect();
+
+        // Returns a parameter where 0 and missing parameters mean 1
+        let count = |idx: usize| values.get(idx).copied().unwrap_or(0).max(1);
+
+        match (private, intermediates, action) {
+            (None, [], b'A') => self.move_up(count(0)),
+            (None, [], b'B' | b'e') => self.move_down(count(0)),
+            (None, [], b'C' | b'a') => {
+                self.move_to(self.cursor.row, self.cursor.col.saturating_add(count(0)))
+            }
+            (None, [], b'D') => {
+                self.move_to(self.cursor.row, self.cursor.col.saturating_sub(count(0)))
+            }
+            (None, [], b'E') => {
+                self.move_down(count(0));
+                self.cursor.col = 0;
+            }
+            (None, [], b'F') => {
+                self.move_up(count(0));
+                self.cursor.col = 0;
+            }
+            (None, [], b'G' | b'`') => self.move_to(self.cursor.row, count(0) - 1),
+            (None, [], b'H' | b'f') => self.move_to_origin_relative(count(0) - 1, count(1) - 1),
+            (None, [], b'd') => self.move_to_origin_relative(count(0) - 1, self.cursor.col),
+            (None, [], b'@') => self.insert_chars(count(0)),
+            (None, [], b'P') => self.delete_chars(count(0)),
+            (None, [], b'X') => self.erase_chars(count(0)),
+            (None, [], b'L') => self.insert_lines(count(0)),
+            (None, [], b'M') => self.delete_lines(count(0)),
+            (None, [], b'S') => self.scroll_up(count(0)),
+            (None, [], b'T') => self.scroll_down(count(0)),
+            (None, [], b'J') => self.erase_display(values[0]),
+            (None, [], b'K') => self.erase_line(values[0]),
+            (None, [], b'm') => apply_sgr(&mut self.pen, params),
+            (None, [], b'b') => {
+                if let Some(character) = self.last_printed {
+                    for _ in 0..count(0) {
+                        self.print(character);
+                    }
+                }
+            }
+            (None, [], b'r') => {
+                let top = count(0) - 1;
+                let bottom = values
+                    .get(1)
+                    .copied()
+                    .filter(|i| *i > 0)
+                    .unwrap_or(self.rows())
+                    .saturating_sub(1);
+
+                if top < bottom && bottom < self.rows() {
+                    self.scroll_top = top;
+                    self.scroll_bottom = bottom;
+                    self.move_to_origin_relative(0, 0);
+                }
+            }
+            (None, [], b's') if params.is_empty() => self.save_cursor(),
+            (None, [], b'u') => self.restore_cursor(),
+            (None, [], b'n') => self.device_status(values[0]),
+            (None, [], b'h') => self.set_modes(values.iter().map(|i| Mode::Ansi(*i)), true),
+            (None, [], b'l') => self.set_modes(values.iter().map(|i| Mode::Ansi(*i)), false),
+            (None, [b'$'], b'p') => self.report_mode(Mode::Ansi(values[0])),
+            (None, [b' '], b'q') => {
+                self.cursor_shape = match values[0] {
+                    1 => CursorShape::BlinkingBlock,
+                    2 => CursorShape::SteadyBlock,
+                    3 => CursorShape::BlinkingUnderline,
+                    4 => CursorShape::SteadyUnderline,
+                    5 => CursorShape::BlinkingBar,
+                    6 => CursorShape::SteadyBar,
+                    _ => CursorShape::Default,
+                }
+            }
+            (Some(b'?'), [], b'h') => self.set_modes(values.iter().map(|i| Mode::Dec(*i)), true),
+            (Some(b'?'), [], b'l') => self.set_modes(values.iter().map(|i| Mode::Dec(*i)), false),
+            (Some(b'?'), [b'$'], b'p') => self.report_mode(Mode::Dec(values[0])),
+            _ => {}
+        }
+    }
+
+    fn set_modes(&mut self, modes: impl Iterator<Item = Mode>, set: bool) {
+        for mode in modes {
+            match mode {
+                Mode::ALTERNATE_BUFFER if set && !self.alternate_active => {
+                    self.save_cursor();
+                    self.alternate_active = true;
+
+                    let blank = self.blank();
+                    self.alternate.clear();
+                    fill(&mut self.alternate, &blank);
+                }
+                Mode::ALTERNATE_BUFFER if !set && self.alternate_active => {
+                    self.alternate_active = false;
+                    self.restore_cursor();
+                }
+                Mode::ORIGIN => {
+                    self.set_mode_flag(mode, set);
+                    self.move_to_origin_relative(0, 0);
+                }
+                _ => {}
+            }
+
+            self.set_mode_flag(mode, set);
+        }
+    }
+
+    fn set_mode_flag(&mut self, mode: Mode, set: bool) {
+        if set {
+            self.modes.insert(mode);
+        } else {
+            self.modes.remove(&mode);
+        }
+    }
+
+    fn report_mode(&mut self, mode: Mode) {
+        // Every mode is recognized, reported as set (1) or reset (2)
+        let code = if self.is_mode_set(mode) { 1 } else { 2 };
+
+        let _ = match mode {
+            Mode::Ansi(value) => write!(self.replies, "\x1b[{};{}$y", value, code),
+            Mode::Dec(value) => write!(self.replies, "\x1b[?{};{}$y", value, code),
+        };
+    }
+
+    fn device_status(&mut self, request: u16) {
+        let _ = match request {
+            5 => write!(self.replies, "\x1b[0n"),
+            6 => {
+                let row = if self.is_mode_set(Mode::ORIGIN) {
+                    self.cursor.row.saturating_sub(self.scroll_top)
+                } else {
+                    self.cursor.row
+                };
+
+                write!(self.replies, "\x1b[{};{}R", row + 1, self.cursor.col + 1)
+            }
+            _ => Ok(()),
+        };
+    }
+
+    fn save_cursor(&mut self) {
+        self.saved = Some(SavedCursor {
+            position: self.cursor,
+            pen: self.pen,
+            origin: self.is_mode_set(Mode::ORIGIN),
+        });
+    }
+
+    fn restore_cursor(&mut self) {
+        let saved = self.saved.unwrap_or(SavedCursor {
+            position: Position::ORIGIN,
+            pen: Style::default(),
+            origin: false,
+        });
+
+        self.pen = saved.pen;
+        self.set_mode_flag(Mode::ORIGIN, saved.origin);
+
+        // The margins may have changed since the cursor was saved
+        let row = if saved.origin {
+            saved
+                .position
+                .row
+                .clamp(self.scroll_top, self.scroll_bottom)
+        } else {
+            saved.position.row
+        };
+
+        self.move_to(row, saved.position.col);
+    }
+
+    /// Moves the cursor, clamped to the screen
+    fn move_to(&mut self, row: u16, col: u16) {
+        self.cursor = Position::new(
+            row.min(self.rows().saturating_sub(1)),
+            col.min(self.cols().saturating_sub(1)),
+        );
+        self.pending_wrap = false;
+    }
+
+    /// Moves the cursor to a position relative to the scroll region in origin mode
+    fn move_to_origin_relative(&mut self, row: u16, col: u16) {
+        if self.is_mode_set(Mode::ORIGIN) {
+            let row = self.scroll_top.saturating_add(row).min(self.scroll_bottom);
+
+            self.move_to(row, col);
+        } else {
+            self.move_to(row, col);
+        }
+    }
+
+    /// Moves the cursor up, stopping at the top margin if the cursor is within the scroll region
+    fn move_up(&mut self, count: u16) {
+        let limit = if self.cursor.row >= self.scroll_top {
+            self.scroll_top
+        } else {
+            0
+        };
+
+        let row = self.cursor.row.saturating_sub(count).max(limit);
+
+        self.move_to(row, self.cursor.col);
+    }
+
+    /// Moves the cursor down, stopping at the bottom margin if the cursor is within the scroll
+    /// region
+    fn move_down(&mut self, count: u16) {
+        let limit = if self.cursor.row <= self.scroll_bottom {
+            self.scroll_bottom
+        } else {
+            self.rows().saturating_sub(1)
+        };
+
+        let row = self.cursor.row.saturating_add(count).min(limit);
+
+        self.move_to(row, self.cursor.col);
+    }
+
+    fn line_feed(&mut self) {
+        self.pending_wrap = false;
+
+        if self.cursor.row == self.scroll_bottom {
+            self.scroll_up(1);
+        } else if self.cursor.row + 1 < self.rows() {
+            self.cursor.row += 1;
+        }
+    }
+
+    fn reverse_index(&mut self) {
+        self.pending_wrap = false;
+
+        if self.cursor.row == self.scroll_top {
+            self.scroll_down(1);
+        } else {
+            self.cursor.row = self.cursor.row.saturating_sub(1);
+        }
+    }
+
+    /// Scrolls the scroll region up. Lines leaving a full screen region of the main screen are
+    /// moved to the scrollback
+    fn scroll_up(&mut self, lines: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let (top, bottom) = (self.scroll_top, self.scroll_bottom);
+        let lines = lines.min(bottom - top + 1);
+
+        if !self.alternate_active && top == 0 && bottom + 1 == self.rows() {
+            for row in 0..lines {
+                self.scrollback.push(self.main.row(row).to_vec());
+            }
+
+            self.trim_scrollback();
+        }
+
+        self.screen_mut().scroll_up(top, bottom, lines);
+        self.fill_rows(bottom + 1 - lines, bottom + 1);
+    }
+
+    fn scroll_down(&mut self, lines: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let (top, bottom) = (self.scroll_top, self.scroll_bottom);
+        let lines = lines.min(bottom - top + 1);
+
+        self.screen_mut().scroll_down(top, bottom, lines);
+        self.fill_rows(top, top + lines);
+    }
+
+    fn insert_lines(&mut self, count: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let row = self.cursor.row;
+
+        if row < self.scroll_top || row > self.scroll_bottom {
+            return;
+        }
+
+        let bottom = self.scroll_bottom;
+        let count = count.min(bottom - row + 1);
+
+        self.screen_mut().scroll_down(row, bottom, count);
+        self.fill_rows(row, row + count);
+        self.move_to(row, 0);
+    }
+
+    fn delete_lines(&mut self, count: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let row = self.cursor.row;
+
+        if row < self.scroll_top || row > self.scroll_bottom {
+            return;
+        }
+
+        let bottom = self.scroll_bottom;
+        let count = count.min(bottom - row + 1);
+
+        self.screen_mut().scroll_up(row, bottom, count);
+        self.fill_rows(bottom + 1 - count, bottom + 1);
+        self.move_to(row, 0);
+    }
+
+    fn insert_chars(&mut self, count: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let Position { row, col } = self.cursor;
+        let mut cells = self.screen().row(row).to_vec();
+        let blank = self.blank();
+
+        for _ in 0..count.min(self.cols() - col) {
+            cells.insert(col as usize, blank.clone());
+        }
+
+        cells.truncate(self.cols() as usize);
+        self.write_row(row, cells);
+        self.pending_wrap = false;
+    }
+
+    fn delete_chars(&mut self, count: u16) {
+        if self.is_empty() {
+            return;
+        }
+
+        let Position { row, col } = self.cursor;
+        let mut cells = self.screen().row(row).to_vec();
+        let count = count.min(self.cols() - col) as usize;
+
+        cells.drain(col as usize..col as usize + count);
+        cells.resize(self.cols() as usize, self.blank());
+
+        self.write_row(row, cells);
+        self.pending_wrap = false;
+    }
+
+    fn erase_chars(&mut self, count: u16) {
+        let Position { row, col } = self.cursor;
+        let end = col.saturating_add(count).min(self.cols());
+
+        self.erase(row, col, end);
+        self.pending_wrap = false;
+    }
+
+    fn erase_line(&mut self, mode: u16) {
+        let Position { row, col } = self.cursor;
+
+        match mode {
+            0 => self.erase(row, col, self.cols()),
+            1 => self.erase(row, 0, col + 1),
+            2 => self.erase(row, 0, self.cols()),
+            _ => {}
+        }
+
+        self.pending_wrap = false;
+    }
+
+    fn erase_display(&mut self, mode: u16) {
+        let Position { row, col } = self.cursor;
+
+        match mode {
+            0 => {
+                self.erase(row, col, self.cols());
+                self.fill_rows(row + 1, self.rows());
+            }
+            1 => {
+                self.fill_rows(0, row);
+                self.erase(row, 0, col + 1);
+            }
+            2 => self.fill_rows(0, self.rows()),
+            3 => self.scrollback.clear(),
+            _ => {}
+        }
+
+        self.pending_wrap = false;
+    }
+
+    /// Replaces the cells of a row from `start` up to `end` (exclusive) with erased cells
+    fn erase(&mut self, row: u16, start: u16, end: u16) {
+        let blank = self.blank();
+
+        for col in start..end {
+            self.screen_mut()
+                .set(Position::new(row, col), blank.clone());
+        }
+    }
+
+    /// Erases the rows from `start` up to `end` (exclusive)
+    fn fill_rows(&mut self, start: u16, end: u16) {
+        for row in start..end.min(self.rows()) {
+            self.erase(row, 0, self.cols());
+        }
+    }
+
+    /// Replaces the cells of a row. Wide characters that were split are replaced with blanks
+    fn write_row(&mut self, row: u16, cells: Vec<Cell>) {
+        self.erase(row, 0, self.cols());
+
+        for (col, cell) in cells.into_iter().enumerate() {
+            if !cell.is_continuation() {
+                self.screen_mut().set(Position::new(row, col as u16), cell);
+            }
+        }
+    }
+
+    fn trim_scrollback(&mut self) {
+        if self.scrollback.len() > self.scrollback_limit {
+            let excess = self.scrollback.len() - self.scrollback_limit;
+
+            self.scrollback.drain(..excess);
+        }
+    }
+}
+
+impl Write for Emulator {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.process(buf);
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn row_text(cells: &[Cell]) -> String {
+    let text: String = cells.iter().map(|cell| cell.symbol()).collect();
+
+    text.trim_end_matches(' ').to_string()
+}
+
+fn fill(buffer: &mut Buffer, cell: &Cell) {
+    for row in 0..buffer.height() {
+        for col in 0..buffer.width() {
+            buffer.set(Position::new(row, col), cell.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        command::{
+            Command, EnterAlternateBuffer, LeaveAlternateBuffer, MoveCursor, Print, ResetStyle,
+            RestoreCursor, SaveCursor, SetScrollRegion, SetStyle,
+        },
+        render::Renderer,
+        style::{Attribute, Color},
+    };
+
+    fn run(emulator: &mut Emulator, command: impl Command) {
+        emulator.process(command.to_ansi().as_bytes());
+    }
+
+    #[test]
+    fn prints_text_and_moves_cursor() {
+        let mut emulator = Emulator::new(Size::new(3, 10));
+
+        run(&mut emulator, Print("hello"));
+        run(&mut emulator, MoveCursor(Position::new(2, 3)));
+        run(&mut emulator, Print("x"));
+
+        assert_eq!(emulator.contents(), "hello\n\n   x");
+        assert_eq!(emulator.cursor(), Position::new(2, 4));
+    }
+
+    #[test]
+    fn wraps_at_the_last_column() {
+        let mut emulator = Emulator::new(Size::new(2, 5));
+
+        run(&mut emulator, Print("abcde"));
+
+        // The cursor stays on the last column until the next character is printed
+        assert_eq!(emulator.cursor(), Position::new(0, 4));
+
+        run(&mut emulator, Print("fg"));
+
+        assert_eq!(emulator.contents(), "abcde\nfg");
+        assert_eq!(emulator.cursor(), Position::new(1, 2));
+    }
+
+    #[test]
+    fn applies_styles() {
+        let mut emulator = Emulator::new(Size::new(1, 5));
+        let style = Style::new()
+            .with_fg(Color::Red)
+            .with_bg(Color::Extended(100))
+            .with_attribute(Attribute::Bold)
+            .with_attribute(Attribute::CurlyUnderline)
+            .with_underline_color(Color::Blue);
+
+        run(&mut emulator, SetStyle(style));
+        run(&mut emulator, Print("a"));
+        run(&mut emulator, ResetStyle);
+        run(&mut emulator, Print("b"));
+
+        assert_eq!(emulator.cell(Position::new(0, 0)).unwrap().style(), &style);
+        assert_eq!(
+            emulator.cell(Position::new(0, 1)).unwrap().style(),
+            &Style::new()
+        );
+    }
+
+    #[test]
+    fn scrolls_within_the_scroll_region() {
+        let mut emulator = Emulator::new(Size::new(4, 5));
+
+        run(&mut emulator, Print("a\r\nb\r\nc\r\nd"));
+        run(&mut emulator, SetScrollRegion { top: 1, bottom: 2 });
+
+        assert_eq!(emulator.scroll_region(), (1, 2));
+        assert_eq!(emulator.cursor(), Position::ORIGIN);
+
+        run(&mut emulator, MoveCursor(Position::new(2, 0)));
+        run(&mut emulator, Print("\ne"));
+
+        assert_eq!(emulator.contents(), "a\nc\ne\nd");
+        assert!(emulator.scrollback().is_empty());
+    }
+
+    #[test]
+    fn alternate_buffer_keeps_the_main_screen() {
+        let mut emulator = Emulator::new(Size::new(2, 5));
+
+        run(&mut emulator, Print("main"));
+        run(&mut emulator, EnterAlternateBuffer);
+
+        assert!(emulator.is_alternate_buffer());
+        assert_eq!(emulator.contents(), "\n");
+
+        run(&mut emulator, Print("alt"));
+        run(&mut emulator, LeaveAlternateBuffer);
+
+        assert!(!emulator.is_alternate_buffer());
+        assert_eq!(emulator.contents(), "main\n");
+        assert_eq!(emulator.cursor(), Position::new(0, 4));
+    }
+
+    #[test]
+    fn restores_saved_cursor() {
+        let mut emulator = Emulator::new(Size::new(3, 10));
+        let style = Style::new().with_fg(Color::Green);
+
+        run(&mut emulator, MoveCursor(Position::new(1, 2)));
+        run(&mut emulator, SetStyle(style));
+        run(&mut emulator, SaveCursor);
+        run(&mut emulator, MoveCursor(Position::new(2, 7)));
+        run(&mut emulator, ResetStyle);
+        run(&mut emulator, RestoreCursor);
+
+        assert_eq!(emulator.cursor(), Position::new(1, 2));
+        assert_eq!(emulator.pen(), style);
+    }
+
+    #[test]
+    fn matches_rendered_frames() {
+        let mut emulator = Emulator::new(Size::new(4, 8));
+        let mut renderer = Renderer::new(Size::new(4, 8));
+        let style = Style::new().with_fg(Color::Cyan);
+
+        let frames = [
+            ["one", "two", "three", ""],
+            ["two", "three", "four", "five"],
+            ["two", "wide \u{4e2d}", "", "five"],
+        ];
+
+        for frame in frames {
+            for (y, line) in frame.iter().enumerate() {
+                renderer
+                    .buffer_mut()
+                    .set_string(Position::new(y as u16, 0), line, style);
+            }
+
+            let mut output = Vec::new();
+            renderer.render(&mut output).unwrap();
+            emulator.process(&output);
+
+            assert_eq!(emulator.contents(), frame.join("\n"));
+            assert_eq!(emulator.screen().cells(), renderer.previous().cells());
+        }
+    }
+
+    #[test]
+    fn empty_screen_does_not_panic() {
+        let mut emulator = Emulator::new(Size::new(0, 0));
+
+        emulator.process(b"\x1b[r\x1b[2Lab\x1b[3P\x1b[S\x1b[T\x1b[6n");
+
+        assert_eq!(emulator.contents(), "");
+    }
+
+    #[test]
+    fn cursor_report_in_origin_mode_after_margins_change() {
+        let mut emulator = Emulator::new(Size::new(10, 20));
+
+        emulator.process(b"\x1b[?6h\x1b[2;5r\x1b7\x1b[6;9r\x1b8\x1b[6n");
+
+        assert_eq!(emulator.cursor(), Position::new(5, 0));
+        assert_eq!(emulator.take_replies(), b"\x1b[1;1R");
+    }
+}
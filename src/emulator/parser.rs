@@ -0,0 +1,180 @@
+/// A unit of terminal output recognized by the `Parser`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Action {
+    /// A printable character
+    Print(char),
+
+    /// A C0 control character such as a line feed
+    Execute(u8),
+
+    /// A control sequence, `CSI <private> <params> <intermediates> <final>`
+    Csi {
+        private: Option<u8>,
+        params: String,
+        intermediates: Vec<u8>,
+        action: u8,
+    },
+
+    /// An escape sequence, `ESC <intermediates> <final>`
+    Esc { intermediates: Vec<u8>, action: u8 },
+
+    /// An OSC, DCS, SOS, PM or APC control string. The content is ignored
+    String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    Escape,
+    Csi,
+    String,
+
+    /// An escape inside a control string, which is either ST or the start of a new sequence
+    StringEscape,
+}
+
+/// An incremental parser for terminal output. Sequences and UTF-8 characters split between calls
+/// to `advance` are completed by the following call
+#[derive(Debug, Clone)]
+pub(crate) struct Parser {
+    state: State,
+    private: Option<u8>,
+    params: String,
+    intermediates: Vec<u8>,
+    utf8: Vec<u8>,
+}
+
+impl Parser {
+    pub fn new() -> Self {
+        Self {
+            state: State::Ground,
+            private: None,
+            params: String::new(),
+            intermediates: Vec::new(),
+            utf8: Vec::new(),
+        }
+    }
+
+    pub fn advance(&mut self, bytes: &[u8]) -> Vec<Action> {
+        let mut actions = Vec::new();
+
+        for byte in bytes {
+            self.byte(*byte, &mut actions);
+        }
+
+        actions
+    }
+
+    fn byte(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        match self.state {
+            State::String => match byte {
+                b'\x07' | b'\x18' | b'\x1a' => {
+                    self.state = State::Ground;
+                    actions.push(Action::String);
+                }
+                b'\x1b' => self.state = State::StringEscape,
+                _ => {}
+            },
+            State::StringEscape => {
+                self.state = State::Ground;
+                actions.push(Action::String);
+
+                if byte != b'\\' {
+                    self.byte(b'\x1b', actions);
+                    self.byte(byte, actions);
+                }
+            }
+            _ if byte == b'\x18' || byte == b'\x1a' => {
+                // CAN and SUB cancel the current sequence
+                self.state = State::Ground;
+                self.utf8.clear();
+            }
+            _ if byte == b'\x1b' => {
+                self.utf8.clear();
+                self.intermediates.clear();
+                self.state = State::Escape;
+            }
+            _ if byte < 0x20 || byte == 0x7f => {
+                // Controls are executed even in the middle of a sequence
+                if byte != 0x7f {
+                    actions.push(Action::Execute(byte));
+                }
+            }
+            State::Ground => self.print(byte, actions),
+            State::Escape => match byte {
+                0x20..=0x2f => self.intermediates.push(byte),
+                b'[' if self.intermediates.is_empty() => {
+                    self.private = None;
+                    self.params.clear();
+                    self.state = State::Csi;
+                }
+                b']' | b'P' | b'X' | b'^' | b'_' if self.intermediates.is_empty() => {
+                    self.state = State::String;
+                }
+                _ => {
+                    self.state = State::Ground;
+                    actions.push(Action::Esc {
+                        intermediates: std::mem::take(&mut self.intermediates),
+                        action: byte,
+                    });
+                }
+            },
+            State::Csi => match byte {
+                b'<' | b'=' | b'>' | b'?' if self.params.is_empty() && self.private.is_none() => {
+                    self.private = Some(byte);
+                }
+                b'0'..=b'9' | b';' | b':' if self.intermediates.is_empty() => {
+                    self.params.push(byte as char);
+                }
+                0x20..=0x2f => self.intermediates.push(byte),
+                0x40..=0x7e => {
+                    self.state = State::Ground;
+                    actions.push(Action::Csi {
+                        private: self.private.take(),
+                        params: std::mem::take(&mut self.params),
+                        intermediates: std::mem::take(&mut self.intermediates),
+                        action: byte,
+                    });
+                }
+                _ => {
+                    // Malformed sequences are dropped
+                    self.state = State::Ground;
+                    self.intermediates.clear();
+                }
+            },
+        }
+    }
+
+    fn print(&mut self, byte: u8, actions: &mut Vec<Action>) {
+        if byte < 0x80 {
+            if !self.utf8.is_empty() {
+                self.utf8.clear();
+                actions.push(Action::Print(char::REPLACEMENT_CHARACTER));
+            }
+
+            actions.push(Action::Print(byte as char));
+            return;
+        }
+
+        self.utf8.push(byte);
+
+        let expected = match self.utf8[0] {
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => 1,
+        };
+
+        if self.utf8.len() < expected {
+            return;
+        }
+
+        let character = std::str::from_utf8(&self.utf8)
+            .ok()
+            .and_then(|i| i.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER);
+
+        self.utf8.clear();
+        actions.push(Action::Print(character));
+    }
+}
@@ -6,6 +6,12 @@ pub mod windows;
 
 use std::vec::IntoIter;
 
+use crate::{
+    geometry::{Position, Size},
+    mode::{Mode, ModeStatus},
+    style::{ColorScheme, Rgb},
+};
+
 #[derive(Debug, Clone)]
 pub struct EventBatch {
     internal: Vec<Event>,
@@ -114,6 +120,26 @@ pub struct KeyEvent {
     pub modifiers: KeyModifiers,
 }
 
+/// Colors of the terminal which are not part of the palette, set and queried with OSC 10, 11 and
+/// 12
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DynamicColor {
+    Foreground,
+    Background,
+    Cursor,
+}
+
+impl DynamicColor {
+    /// Returns the OSC number used to set or query this color
+    pub fn code(&self) -> u8 {
+        match self {
+            DynamicColor::Foreground => 10,
+            DynamicColor::Background => 11,
+            DynamicColor::Cursor => 12,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum Event {
     Key(KeyEvent),
@@ -121,7 +147,25 @@ pub enum Event {
     FocusGained,
     FocusLost,
 
-    Cursor(u16, u16),
+    /// A reply to a cursor position request, 0-based
+    Cursor(Position),
+
+    /// A reply to a DECRQM mode query
+    ModeReport(Mode, ModeStatus),
+
+    /// A reply to an OSC 10, 11 or 12 color query
+    DynamicColor(DynamicColor, Rgb),
+
+    /// A reply to an OSC 4 palette color query
+    PaletteColor(u8, Rgb),
+
+    /// The terminal switched between a dark and light color scheme. This is sent while mode 2031
+    /// is set and in reply to a color scheme query
+    ColorSchemeChanged(ColorScheme),
+
+    /// The screen changed size. Only scripted with `TestBackend::resize` for now, `TtyBackend`
+    /// does not report resizes so applications poll `Terminal::size` instead
+    Resize(Size),
 
     UnrecognizedControlSequence,
     OutOfRange,
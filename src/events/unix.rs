@@ -1,12 +1,15 @@
-use crate::events::{Event, EventBatch, KeyCode, KeyEvent, KeyModifiers};
+use crate::{
+    events::{DynamicColor, Event, EventBatch, KeyCode, KeyEvent, KeyModifiers},
+    geometry::Position,
+    mode::{Mode, ModeStatus},
+    style::{ColorScheme, Rgb},
+};
 
 pub fn parse_event<I>(mut bytes: I) -> Option<Event>
 where
     I: Iterator<Item = u8>,
 {
-    let Some(byte) = bytes.next() else {
-        return None;
-    };
+    let byte = bytes.next()?;
 
     match byte {
         b'\x1b' => Some(parse_esc(bytes)),
@@ -25,6 +28,7 @@ where
 
     match byte {
         b'[' => parse_opening_bracket(bytes),
+        b']' => parse_osc(bytes),
         b'O' => parse_opening_o(bytes),
 
         b'\x1b' => KeyCode::Escape.into(),
@@ -54,12 +58,14 @@ where
             modifiers: KeyModifiers::Shift,
         }),
 
-        b'M' => unimplemented!("Mouse not handled yet"),
-        b'<' => unimplemented!("Mouse not handled yet"),
+        b'M' => parse_x10_mouse(bytes),
+        b'<' => parse_sgr_mouse(bytes),
 
         b'I' => Event::FocusGained,
         b'O' => Event::FocusLost,
 
+        b'?' => parse_private(bytes),
+
         val @ b'0'..=b'9' => parse_numerical(bytes, val),
 
         _ => Event::UnrecognizedControlSequence,
@@ -117,32 +123,319 @@ where
                 current_idx += 1;
             }
             b'R' if registers.len() == 2 => {
-                let Ok(Ok(x)) = String::from_utf8(registers.swap_remove(0)).map(|x| x.parse::<u16>()) else {
+                let Ok(Ok(row)) = String::from_utf8(registers.swap_remove(0)).map(|x| x.parse::<u16>()) else {
                     break Event::UnrecognizedControlSequence;
                 };
 
-                let Ok(Ok(y)) = String::from_utf8(registers.swap_remove(0)).map(|x| x.parse::<u16>()) else {
+                let Ok(Ok(col)) = String::from_utf8(registers.swap_remove(0)).map(|x| x.parse::<u16>()) else {
+                    break Event::UnrecognizedControlSequence;
+                };
+
+                // Terminals report 1-based coordinates
+                break Event::Cursor(Position::new(row.saturating_sub(1), col.saturating_sub(1)));
+            }
+            b'$' if registers.len() == 2 => {
+                break parse_mode_report(bytes, registers, Mode::Ansi);
+            }
+            _ => break Event::UnrecognizedControlSequence,
+        }
+    }
+}
+
+/// "\x1b[?"
+pub fn parse_private<I>(mut bytes: I) -> Event
+where
+    I: Iterator<Item = u8>,
+{
+    let mut registers = vec![vec![]];
+
+    let mut len = 4;
+    let mut current_idx = 0;
+
+    loop {
+        len += 1;
+
+        if len > 126 {
+            break Event::UnrecognizedControlSequence;
+        }
+
+        let Some(byte) = bytes.next() else {
+            return Event::UnrecognizedControlSequence;
+        };
+
+        match byte {
+            val @ b'0'..=b'9' => registers[current_idx].push(val),
+            b';' => {
+                registers.push(vec![]);
+                current_idx += 1;
+            }
+            b'$' if registers.len() == 2 => {
+                break parse_mode_report(bytes, registers, Mode::Dec);
+            }
+            b'n' if registers.len() == 2 => {
+                let Some(997) = parse_register(registers.swap_remove(0)) else {
                     break Event::UnrecognizedControlSequence;
                 };
 
-                break Event::Cursor(x, y);
+                break match parse_register(registers.swap_remove(0)) {
+                    Some(1) => Event::ColorSchemeChanged(ColorScheme::Dark),
+                    Some(2) => Event::ColorSchemeChanged(ColorScheme::Light),
+                    _ => Event::UnrecognizedControlSequence,
+                };
             }
             _ => break Event::UnrecognizedControlSequence,
         }
     }
 }
 
+/// "\x1b[?#;#$" or "\x1b[#;#$"
+fn parse_mode_report<I>(mut bytes: I, mut registers: Vec<Vec<u8>>, kind: fn(u16) -> Mode) -> Event
+where
+    I: Iterator<Item = u8>,
+{
+    if bytes.next() != Some(b'y') {
+        return Event::UnrecognizedControlSequence;
+    }
+
+    let Some(mode) = parse_register(registers.swap_remove(0)) else {
+        return Event::UnrecognizedControlSequence;
+    };
+
+    let Some(status) = parse_register(registers.swap_remove(0)) else {
+        return Event::UnrecognizedControlSequence;
+    };
+
+    Event::ModeReport(kind(mode), ModeStatus::from_code(status))
+}
+
+/// "\x1b[M", mouse reports are consumed but not yet decoded
+fn parse_x10_mouse<I>(bytes: I) -> Event
+where
+    I: Iterator<Item = u8>,
+{
+    // Button, column and row are each a single byte
+    bytes.take(3).for_each(drop);
+
+    Event::UnrecognizedControlSequence
+}
+
+/// "\x1b[<", mouse reports are consumed but not yet decoded
+fn parse_sgr_mouse<I>(bytes: I) -> Event
+where
+    I: Iterator<Item = u8>,
+{
+    // Parameters are terminated by 'M' on press and 'm' on release
+    for byte in bytes.take(126) {
+        if !matches!(byte, b'0'..=b'9' | b';') {
+            break;
+        }
+    }
+
+    Event::UnrecognizedControlSequence
+}
+
+/// "\x1b]"
+pub fn parse_osc<I>(mut bytes: I) -> Event
+where
+    I: Iterator<Item = u8>,
+{
+    let mut content = Vec::new();
+
+    // Terminated by either BEL or ST, "\x1b\\"
+    loop {
+        if content.len() > 512 {
+            return Event::UnrecognizedControlSequence;
+        }
+
+        match bytes.next() {
+            Some(b'\x07') => break,
+            Some(b'\x1b') => match bytes.next() {
+                Some(b'\\') => break,
+                _ => return Event::UnrecognizedControlSequence,
+            },
+            Some(byte) => content.push(byte),
+            None => return Event::UnrecognizedControlSequence,
+        }
+    }
+
+    let Ok(content) = String::from_utf8(content) else {
+        return Event::UnrecognizedControlSequence;
+    };
+
+    let Some((code, value)) = content.split_once(';') else {
+        return Event::UnrecognizedControlSequence;
+    };
+
+    if code == "4" {
+        let Some((index, value)) = value.split_once(';') else {
+            return Event::UnrecognizedControlSequence;
+        };
+
+        return match (index.parse::<u8>(), parse_color_spec(value)) {
+            (Ok(index), Some(rgb)) => Event::PaletteColor(index, rgb),
+            _ => Event::UnrecognizedControlSequence,
+        };
+    }
+
+    let kind = match code {
+        "10" => DynamicColor::Foreground,
+        "11" => DynamicColor::Background,
+        "12" => DynamicColor::Cursor,
+        _ => return Event::UnrecognizedControlSequence,
+    };
+
+    match parse_color_spec(value) {
+        Some(rgb) => Event::DynamicColor(kind, rgb),
+        None => Event::UnrecognizedControlSequence,
+    }
+}
+
+/// Parses the X11 color specification used in OSC replies, `rgb:rrrr/gggg/bbbb` where each
+/// channel has 1 to 4 hex digits
+fn parse_color_spec(spec: &str) -> Option<Rgb> {
+    let Some(channels) = spec.strip_prefix("rgb:") else {
+        return spec.parse::<Rgb>().ok();
+    };
+
+    let mut channels = channels.split('/').map(|channel| {
+        if channel.is_empty() || channel.len() > 4 {
+            return None;
+        }
+
+        let value = u32::from_str_radix(channel, 16).ok()?;
+        let max = (1 << (4 * channel.len())) - 1;
+
+        Some(((value * 255 + max / 2) / max) as u8)
+    });
+
+    let rgb = Rgb::new(channels.next()??, channels.next()??, channels.next()??);
+
+    channels.next().is_none().then_some(rgb)
+}
+
+fn parse_register(register: Vec<u8>) -> Option<u16> {
+    String::from_utf8(register).ok()?.parse::<u16>().ok()
+}
+
 pub fn parse_batch(sequence: Vec<u8>) -> EventBatch {
     let mut batch: Vec<Event> = Vec::new();
-    let mut bytes = sequence.into_iter();
+    let mut bytes = sequence.iter();
 
     loop {
-        if let Some(e) = parse_event(&mut bytes) {
-            batch.push(e);
-        } else {
-            break;
+        // "\x1b]" is also sent for Alt+']', which is only an OSC reply if it is terminated
+        if let Some(rest) = bytes.as_slice().strip_prefix(b"\x1b]") {
+            if !has_osc_terminator(rest) {
+                bytes.nth(1);
+                batch.push(Event::Key(KeyEvent {
+                    code: KeyCode::Char(']'),
+                    modifiers: KeyModifiers::Alt,
+                }));
+
+                continue;
+            }
         }
+
+        let Some(e) = parse_event(bytes.by_ref().copied()) else {
+            break;
+        };
+
+        batch.push(e);
     }
 
     batch.into()
 }
+
+fn has_osc_terminator(bytes: &[u8]) -> bool {
+    bytes.contains(&b'\x07') || bytes.windows(2).any(|i| i == b"\x1b\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(bytes: &[u8]) -> Vec<Event> {
+        parse_batch(bytes.to_vec()).into_iter().collect()
+    }
+
+    #[test]
+    fn mode_reports() {
+        assert_eq!(
+            parse(b"\x1b[?2026;2$y\x1b[4;1$y\x1b[?25;4$y\x1b[?9999;0$y"),
+            vec![
+                Event::ModeReport(Mode::Dec(2026), ModeStatus::Reset),
+                Event::ModeReport(Mode::Ansi(4), ModeStatus::Set),
+                Event::ModeReport(Mode::Dec(25), ModeStatus::PermanentlyReset),
+                Event::ModeReport(Mode::Dec(9999), ModeStatus::NotRecognized),
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_mode_reports() {
+        // A wrong final byte, a missing status and an out of range mode
+        assert_eq!(
+            parse(b"\x1b[?2026;2$x"),
+            vec![Event::UnrecognizedControlSequence]
+        );
+        assert_eq!(
+            parse(b"\x1b[?2026;$y"),
+            vec![Event::UnrecognizedControlSequence]
+        );
+        assert_eq!(
+            parse(b"\x1b[?99999;1$y"),
+            vec![Event::UnrecognizedControlSequence]
+        );
+    }
+
+    #[test]
+    fn osc_replies() {
+        let background = Event::DynamicColor(DynamicColor::Background, Rgb::new(0x28, 0x2c, 0x34));
+
+        assert_eq!(
+            parse(b"\x1b]11;rgb:2828/2c2c/3434\x07"),
+            vec![background.clone()]
+        );
+        assert_eq!(
+            parse(b"\x1b]11;rgb:28/2c/34\x1b\\\x1b]4;1;rgb:ff/00/00\x07"),
+            vec![background, Event::PaletteColor(1, Rgb::new(255, 0, 0))]
+        );
+    }
+
+    #[test]
+    fn unterminated_osc_is_alt_bracket() {
+        let alt_bracket = Event::Key(KeyEvent {
+            code: KeyCode::Char(']'),
+            modifiers: KeyModifiers::Alt,
+        });
+
+        assert_eq!(parse(b"\x1b]"), vec![alt_bracket.clone()]);
+        assert_eq!(
+            parse(b"\x1b]11"),
+            vec![
+                alt_bracket,
+                KeyCode::Char('1').into(),
+                KeyCode::Char('1').into()
+            ]
+        );
+    }
+
+    #[test]
+    fn mouse_reports_are_consumed() {
+        assert_eq!(
+            parse(b"\x1b[<0;10;20Ma\x1b[<0;10;20mb"),
+            vec![
+                Event::UnrecognizedControlSequence,
+                KeyCode::Char('a').into(),
+                Event::UnrecognizedControlSequence,
+                KeyCode::Char('b').into(),
+            ]
+        );
+        assert_eq!(
+            parse(b"\x1b[M !!c"),
+            vec![
+                Event::UnrecognizedControlSequence,
+                KeyCode::Char('c').into()
+            ]
+        );
+    }
+}
@@ -0,0 +1,63 @@
+/// A position on the screen. Rows and columns are 0-based, so the top left corner is `(0, 0)`.
+/// Conversion to the 1-based coordinates used by terminals happens when writing or parsing
+/// sequences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Position {
+    pub row: u16,
+    pub col: u16,
+}
+
+impl Position {
+    /// The top left corner of the screen
+    pub const ORIGIN: Position = Position::new(0, 0);
+
+    pub const fn new(row: u16, col: u16) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<(u16, u16)> for Position {
+    /// Converts a `(row, col)` tuple
+    fn from((row, col): (u16, u16)) -> Self {
+        Self::new(row, col)
+    }
+}
+
+/// The size of a screen or area in rows and columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Size {
+    pub rows: u16,
+    pub cols: u16,
+}
+
+impl Size {
+    pub const fn new(rows: u16, cols: u16) -> Self {
+        Self { rows, cols }
+    }
+
+    /// Returns whether a position lies within an area of this size starting at the origin
+    pub fn contains(&self, position: Position) -> bool {
+        position.row < self.rows && position.col < self.cols
+    }
+
+    /// Returns the position of the bottom right cell, or `None` if the size is empty
+    pub fn last(&self) -> Option<Position> {
+        if self.rows == 0 || self.cols == 0 {
+            return None;
+        }
+
+        Some(Position::new(self.rows - 1, self.cols - 1))
+    }
+
+    /// Returns the number of cells
+    pub fn area(&self) -> usize {
+        self.rows as usize * self.cols as usize
+    }
+}
+
+impl From<(u16, u16)> for Size {
+    /// Converts a `(rows, cols)` tuple
+    fn from((rows, cols): (u16, u16)) -> Self {
+        Self::new(rows, cols)
+    }
+}
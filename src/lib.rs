@@ -6,11 +6,35 @@
 //! Termip aims to provide a consistent and thin abstraction over platform specific sys calls and
 //! terminal handling.
 
+/// A module providing backends abstracting over a real terminal and an in-memory one for tests
+pub mod backend;
+
+/// A module providing detection of terminal capabilities from the environment
+pub mod capabilities;
+
+/// A module providing commands, terminal operations which can be collected and written later
+pub mod command;
+
+/// A module providing a headless terminal emulator for checking output in tests
+pub mod emulator;
+
 /// A module providing event structures for handling input from a terminal
 pub mod events;
 
+/// A module providing position and size types with 0-based coordinates
+pub mod geometry;
+
+/// A module providing terminal mode definitions used for setting and querying modes
+pub mod mode;
+
+/// A module providing a cell buffer and diff based rendering for full screen applications
+pub mod render;
+
 /// A module providing styling functionality for the terminal
 pub mod style;
 
+/// A module providing a terminal handle which tracks and restores the modes it changes
+pub mod terminal;
+
 /// A module providing utilities to manipulate the terminal 
 pub mod utils;
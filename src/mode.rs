@@ -0,0 +1,106 @@
+/// A terminal mode which can be set, reset or queried with DECRQM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// A standard ANSI mode, addressed as `CSI n h`
+    Ansi(u16),
+
+    /// A DEC private mode, addressed as `CSI ? n h`
+    Dec(u16),
+}
+
+impl Mode {
+    /// Insert/replace mode (IRM)
+    pub const INSERT: Mode = Mode::Ansi(4);
+
+    /// Automatic newline mode (LNM)
+    pub const AUTOMATIC_NEWLINE: Mode = Mode::Ansi(20);
+
+    /// Application cursor keys (DECCKM)
+    pub const APPLICATION_CURSOR_KEYS: Mode = Mode::Dec(1);
+
+    /// Origin mode (DECOM)
+    pub const ORIGIN: Mode = Mode::Dec(6);
+
+    /// Auto wrap mode (DECAWM)
+    pub const AUTO_WRAP: Mode = Mode::Dec(7);
+
+    /// Blinking cursor
+    pub const CURSOR_BLINK: Mode = Mode::Dec(12);
+
+    /// Cursor visibility (DECTCEM)
+    pub const CURSOR_VISIBLE: Mode = Mode::Dec(25);
+
+    /// Mouse button press and release reporting
+    pub const MOUSE_CLICKS: Mode = Mode::Dec(1000);
+
+    /// Mouse reporting of motion while a button is held, in addition to presses and releases
+    pub const MOUSE_DRAG: Mode = Mode::Dec(1002);
+
+    /// Mouse reporting of all motion, in addition to presses and releases
+    pub const MOUSE_MOTION: Mode = Mode::Dec(1003);
+
+    /// SGR encoding of mouse reports, which is not limited to 223 rows and columns
+    pub const SGR_MOUSE: Mode = Mode::Dec(1006);
+
+    /// Focus in and focus out reporting
+    pub const FOCUS_EVENTS: Mode = Mode::Dec(1004);
+
+    /// Alternate screen buffer, saving the cursor on entry
+    pub const ALTERNATE_BUFFER: Mode = Mode::Dec(1049);
+
+    /// Bracketed paste
+    pub const BRACKETED_PASTE: Mode = Mode::Dec(2004);
+
+    /// Synchronized output, the terminal holds rendering until the mode is reset
+    pub const SYNCHRONIZED_OUTPUT: Mode = Mode::Dec(2026);
+
+    /// Grapheme cluster aware cursor movement and width calculation
+    pub const GRAPHEME_CLUSTERING: Mode = Mode::Dec(2027);
+
+    /// Color scheme (dark/light) change notifications
+    pub const COLOR_SCHEME_UPDATES: Mode = Mode::Dec(2031);
+
+    /// In band window resize notifications
+    pub const IN_BAND_RESIZE: Mode = Mode::Dec(2048);
+
+    /// Returns the numerical value of the mode, without its ANSI or DEC prefix
+    pub fn value(&self) -> u16 {
+        match self {
+            Mode::Ansi(value) | Mode::Dec(value) => *value,
+        }
+    }
+}
+
+/// The state of a mode as reported by the terminal in response to a DECRQM query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModeStatus {
+    NotRecognized,
+    Set,
+    Reset,
+    PermanentlySet,
+    PermanentlyReset,
+}
+
+impl ModeStatus {
+    /// Converts the `v` parameter of a `CSI ? n ; v $ y` reply into a status. Values outside of
+    /// the specification are treated as not recognized
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1 => ModeStatus::Set,
+            2 => ModeStatus::Reset,
+            3 => ModeStatus::PermanentlySet,
+            4 => ModeStatus::PermanentlyReset,
+            _ => ModeStatus::NotRecognized,
+        }
+    }
+
+    /// Returns whether the mode is currently set, either temporarily or permanently
+    pub fn is_set(&self) -> bool {
+        matches!(self, ModeStatus::Set | ModeStatus::PermanentlySet)
+    }
+
+    /// Returns whether the terminal recognizes the mode and allows changing it
+    pub fn is_supported(&self) -> bool {
+        matches!(self, ModeStatus::Set | ModeStatus::Reset)
+    }
+}
@@ -0,0 +1,364 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+use crate::{
+    geometry::{Position, Size},
+    style::{Style, Text},
+};
+
+/// A single cell of a `Buffer`, holding one grapheme and its style. A wide grapheme occupies its
+/// own cell and a continuation cell to its right, which has no symbol of its own.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cell {
+    symbol: String,
+    style: Style,
+    width: u8,
+}
+
+impl Cell {
+    /// Creates a cell from a single grapheme. Graphemes wider than 2 columns are clamped to 2 and
+    /// graphemes without a width, such as control characters, are replaced with a space
+    pub fn new(symbol: &str, style: Style) -> Self {
+        let width = symbol.width().min(2) as u8;
+
+        if width == 0 {
+            return Self::blank(style);
+        }
+
+        Self {
+            symbol: symbol.to_string(),
+            style,
+            width,
+        }
+    }
+
+    /// Creates a space with the given style
+    pub fn blank(style: Style) -> Self {
+        Self {
+            symbol: " ".into(),
+            style,
+            width: 1,
+        }
+    }
+
+    /// The cell covered by the right half of a wide grapheme
+    fn continuation(style: Style) -> Self {
+        Self {
+            symbol: String::new(),
+            style,
+            width: 0,
+        }
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// Returns the number of columns the grapheme occupies, `0` for continuation cells
+    pub fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    /// Returns whether this cell is covered by a wide grapheme to its left
+    pub fn is_continuation(&self) -> bool {
+        self.width == 0
+    }
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self::blank(Style::default())
+    }
+}
+
+/// A grid of cells representing the contents of the screen. Positions are 0-based rows and
+/// columns from the top left corner.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// Creates a buffer filled with blank cells
+    pub fn new(size: Size) -> Self {
+        Self {
+            width: size.cols,
+            height: size.rows,
+            cells: vec![Cell::default(); size.area()],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        Size::new(self.height, self.width)
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn index(&self, x: u16, y: u16) -> Option<usize> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        Some(y as usize * self.width as usize + x as usize)
+    }
+
+    pub fn get(&self, position: Position) -> Option<&Cell> {
+        self.cells.get(self.index(position.col, position.row)?)
+    }
+
+    /// Returns the cells of a single row, or an empty slice if `row` is outside the buffer
+    pub fn row(&self, row: u16) -> &[Cell] {
+        let start = row as usize * self.width as usize;
+
+        self.cells
+            .get(start..start + self.width as usize)
+            .unwrap_or_default()
+    }
+
+    /// Places a cell, keeping wide graphemes intact. A wide grapheme that does not fit in the last
+    /// column is replaced with a space, and any wide grapheme partially overwritten is replaced
+    /// with spaces
+    pub fn set(&mut self, position: Position, cell: Cell) {
+        let Position { row: y, col: x } = position;
+
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+
+        self.clear_wide(x, y);
+
+        if cell.width() == 2 {
+            if x + 1 >= self.width {
+                self.cells[idx] = Cell::blank(cell.style);
+                return;
+            }
+
+            self.clear_wide(x + 1, y);
+            self.cells[idx + 1] = Cell::continuation(cell.style);
+        }
+
+        self.cells[idx] = cell;
+    }
+
+    /// Replaces a wide grapheme covering `(x, y)` with spaces
+    fn clear_wide(&mut self, x: u16, y: u16) {
+        let Some(idx) = self.index(x, y) else {
+            return;
+        };
+
+        let style = self.cells[idx].style;
+
+        if self.cells[idx].is_continuation() {
+            self.cells[idx] = Cell::blank(style);
+
+            if x > 0 {
+                let style = self.cells[idx - 1].style;
+                self.cells[idx - 1] = Cell::blank(style);
+            }
+        } else if self.cells[idx].width() == 2 && x + 1 < self.width {
+            self.cells[idx + 1] = Cell::blank(style);
+        }
+    }
+
+    /// Writes a string starting at `position`, clipped at the end of the row. Returns the number
+    /// of columns written
+    pub fn set_string(&mut self, position: Position, string: &str, style: Style) -> u16 {
+        let mut column = position.col;
+
+        for grapheme in string.graphemes(true) {
+            if grapheme.width() == 0 {
+                continue;
+            }
+
+            let cell = Cell::new(grapheme, style);
+
+            if column.saturating_add(cell.width() as u16) > self.width {
+                break;
+            }
+
+            column += cell.width() as u16;
+
+            self.set(
+                Position::new(position.row, column - cell.width() as u16),
+                cell,
+            );
+        }
+
+        column - position.col
+    }
+
+    /// Writes styled text starting at `position`, clipped at the end of the row. Returns the
+    /// number of columns written
+    pub fn set_text(&mut self, position: Position, text: &Text) -> u16 {
+        let mut column = position.col;
+
+        for span in text.spans.iter() {
+            column += self.set_string(
+                Position::new(position.row, column),
+                &span.content,
+                span.style,
+            );
+        }
+
+        column - position.col
+    }
+
+    /// Sets the style of every cell, keeping their contents
+    pub fn set_style(&mut self, style: Style) {
+        for cell in self.cells.iter_mut() {
+            cell.style = style;
+        }
+    }
+
+    /// Replaces every cell with a blank cell
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::default());
+    }
+
+    /// Moves the rows from `top` to `bottom` (inclusive) up by `lines`, like a terminal scrolling
+    /// within a scroll region. Rows exposed at the bottom are blank
+    pub fn scroll_up(&mut self, top: u16, bottom: u16, lines: u16) {
+        let width = self.width as usize;
+
+        let Some(region) = self.region(top, bottom) else {
+            return;
+        };
+
+        let shift = (lines as usize * width).min(region.len());
+
+        region.rotate_left(shift);
+
+        let len = region.len();
+        region[len - shift..].fill(Cell::default());
+    }
+
+    /// Moves the rows from `top` to `bottom` (inclusive) down by `lines`, like a terminal
+    /// scrolling within a scroll region. Rows exposed at the top are blank
+    pub fn scroll_down(&mut self, top: u16, bottom: u16, lines: u16) {
+        let width = self.width as usize;
+
+        let Some(region) = self.region(top, bottom) else {
+            return;
+        };
+
+        let shift = (lines as usize * width).min(region.len());
+
+        region.rotate_right(shift);
+        region[..shift].fill(Cell::default());
+    }
+
+    fn region(&mut self, top: u16, bottom: u16) -> Option<&mut [Cell]> {
+        if top > bottom || bottom >= self.height {
+            return None;
+        }
+
+        let width = self.width as usize;
+
+        Some(&mut self.cells[top as usize * width..(bottom as usize + 1) * width])
+    }
+
+    /// Changes the size of the buffer. Contents are kept where they fit and new cells are blank
+    pub fn resize(&mut self, size: Size) {
+        let mut resized = Buffer::new(size);
+
+        for y in 0..size.rows.min(self.height) {
+            for x in 0..size.cols.min(self.width) {
+                let position = Position::new(y, x);
+
+                if let Some(cell) = self.get(position) {
+                    if !cell.is_continuation() {
+                        resized.set(position, cell.clone());
+                    }
+                }
+            }
+        }
+
+        *self = resized;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the symbols of a row, continuation cells are shown as `_`
+    fn row(buffer: &Buffer, row: u16) -> String {
+        buffer
+            .row(row)
+            .iter()
+            .map(|i| if i.is_continuation() { "_" } else { i.symbol() })
+            .collect()
+    }
+
+    #[test]
+    fn wide_cell_in_last_column() {
+        let mut buffer = Buffer::new(Size::new(1, 3));
+
+        buffer.set(Position::new(0, 2), Cell::new("好", Style::default()));
+        assert_eq!(row(&buffer, 0), "   ");
+
+        assert_eq!(
+            buffer.set_string(Position::new(0, 0), "a好好", Style::default()),
+            3
+        );
+        assert_eq!(row(&buffer, 0), "a好_");
+    }
+
+    #[test]
+    fn overwriting_half_of_a_wide_cell() {
+        let mut buffer = Buffer::new(Size::new(1, 4));
+
+        buffer.set_string(Position::new(0, 0), "好好", Style::default());
+        assert_eq!(row(&buffer, 0), "好_好_");
+
+        buffer.set(Position::new(0, 1), Cell::new("a", Style::default()));
+        assert_eq!(row(&buffer, 0), " a好_");
+
+        buffer.set(Position::new(0, 2), Cell::new("b", Style::default()));
+        assert_eq!(row(&buffer, 0), " ab ");
+
+        buffer.set_string(Position::new(0, 0), "好", Style::default());
+        buffer.set(Position::new(0, 1), Cell::new("好", Style::default()));
+        assert_eq!(row(&buffer, 0), " 好_ ");
+    }
+
+    #[test]
+    fn resize() {
+        let mut buffer = Buffer::new(Size::new(2, 4));
+
+        buffer.set_string(Position::new(0, 0), "ab好", Style::default());
+        buffer.set_string(Position::new(1, 0), "cdef", Style::default());
+
+        buffer.resize(Size::new(1, 3));
+        assert_eq!(buffer.size(), Size::new(1, 3));
+        assert_eq!(row(&buffer, 0), "ab ");
+
+        buffer.resize(Size::new(2, 5));
+        assert_eq!(row(&buffer, 0), "ab   ");
+        assert_eq!(row(&buffer, 1), "     ");
+        assert_eq!(buffer.cells().len(), 10);
+
+        // Rows outside the buffer are empty
+        assert!(buffer.row(2).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use std::io::Write;
+
+use crate::geometry::{Position, Size};
+
+mod buffer;
+mod optimizer;
+mod scroll;
+
+pub use buffer::{Buffer, Cell};
+pub use optimizer::{Optimizations, RenderStats};
+
+use optimizer::Emitter;
+use scroll::Shift;
+
+/// Draws frames by comparing each new buffer against the previous frame and writing only the
+/// cells that changed.
+///
+/// Draw into `buffer_mut` and call `render`. After rendering, the drawn buffer becomes the
+/// previous frame and `buffer_mut` starts out blank again.
+pub struct Renderer {
+    previous: Buffer,
+    current: Buffer,
+    invalidated: bool,
+    optimizations: Optimizations,
+}
+
+impl Renderer {
+    /// Creates a renderer for a screen of the given size. The first frame is drawn in full
+    pub fn new(size: Size) -> Self {
+        Self {
+            previous: Buffer::new(size),
+            current: Buffer::new(size),
+            invalidated: true,
+            optimizations: Optimizations::default(),
+        }
+    }
+
+    /// Returns the buffer of the next frame
+    pub fn buffer_mut(&mut self) -> &mut Buffer {
+        &mut self.current
+    }
+
+    /// Returns the last rendered frame
+    pub fn previous(&self) -> &Buffer {
+        &self.previous
+    }
+
+    /// Changes the size of the screen. The next frame is drawn in full since the terminal might
+    /// have reflowed or cleared its contents
+    pub fn resize(&mut self, size: Size) {
+        self.previous = Buffer::new(size);
+        self.current.resize(size);
+        self.invalidated = true;
+    }
+
+    /// Returns the optimizations used when writing frames
+    pub fn optimizations(&self) -> Optimizations {
+        self.optimizations
+    }
+
+    /// Changes the optimizations used when writing frames
+    pub fn set_optimizations(&mut self, optimizations: Optimizations) {
+        self.optimizations = optimizations;
+    }
+
+    /// Draws the next frame in full, for example after the screen was cleared by another program
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
+    /// Writes the changes between the previous frame and the next frame, choosing the cheapest
+    /// sequences for cursor motion and runs of cells. The position of the cursor is undefined
+    /// afterwards and the pen is reset. Nothing is flushed
+    pub fn render<Output>(&mut self, output: &mut Output) -> std::io::Result<RenderStats>
+    where
+        Output: Write,
+    {
+        let mut prefix = Vec::new();
+
+        if self.invalidated {
+            prefix.extend_from_slice(b"\x1b[0m\x1b[2J");
+
+            self.previous = Buffer::new(self.current.size());
+            self.invalidated = false;
+        }
+
+        let changes = diff(&self.previous, &self.current);
+        let mut frame = self.emit(&prefix, &changes);
+
+        let mut stats = RenderStats {
+            cells: changes.len(),
+            bytes: frame.len(),
+            naive_bytes: prefix.len() + optimizer::naive_cost(&changes),
+            scrolled: false,
+        };
+
+        // Scrolling is only worth it if drawing the remaining changes is cheaper
+        let shift = self
+            .optimizations
+            .scroll
+            .then(|| Shift::detect(&self.previous, &self.current))
+            .flatten();
+
+        if let Some(shift) = shift {
+            let mut shifted = self.previous.clone();
+            shift.apply(&mut shifted);
+
+            prefix.extend_from_slice(shift.sequence(self.current.height()).as_bytes());
+
+            let scrolled = self.emit(&prefix, &diff(&shifted, &self.current));
+
+            if scrolled.len() < frame.len() {
+                frame = scrolled;
+                stats.bytes = frame.len();
+                stats.scrolled = true;
+            }
+        }
+
+        output.write_all(&frame)?;
+
+        std::mem::swap(&mut self.previous, &mut self.current);
+        self.current.clear();
+
+        Ok(stats)
+    }
+
+    fn emit(&self, prefix: &[u8], changes: &[(Position, &Cell)]) -> Vec<u8> {
+        let mut emitter = Emitter::new(&self.current, self.optimizations);
+
+        emitter.write_raw(prefix);
+        emitter.draw(changes);
+        emitter.finish();
+
+        emitter.output
+    }
+}
+
+/// Returns the cells of `next` that differ from `previous`, in drawing order. Continuation cells
+/// are never returned since they are drawn by the wide grapheme to their left. Buffers of
+/// different sizes are compared as if `previous` were blank
+pub fn diff<'a>(previous: &Buffer, next: &'a Buffer) -> Vec<(Position, &'a Cell)> {
+    let same_size = previous.size() == next.size();
+    let blank = Cell::default();
+
+    let mut changes = Vec::new();
+
+    for row in 0..next.height() {
+        for col in 0..next.width() {
+            let position = Position::new(row, col);
+
+            let Some(cell) = next.get(position) else {
+                continue;
+            };
+
+            if cell.is_continuation() {
+                continue;
+            }
+
+            let before = if same_size {
+                previous.get(position).unwrap_or(&blank)
+            } else {
+                &blank
+            };
+
+            // A wide grapheme replacing narrow ones has to be drawn even if its own cell matches
+            let covered = Position::new(row, col + 1);
+            let covered_changed = cell.width() == 2 && previous.get(covered) != next.get(covered);
+
+            if before != cell || (same_size && covered_changed) {
+                changes.push((position, cell));
+            }
+        }
+    }
+
+    changes
+}
@@ -0,0 +1,492 @@
+use crate::{geometry::Position, style::Style};
+
+use super::{Buffer, Cell};
+
+/// Output optimizations used by the renderer. Cursor motion, erasing and scrolling only use
+/// sequences supported by every VT220 compatible terminal. `repeat` uses REP, which is missing from some
+/// terminals such as the Linux console and is therefore disabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Optimizations {
+    /// Choose between absolute, relative and carriage return/line feed cursor motion, or
+    /// re-printing the cells in between, whichever is cheapest
+    pub cursor_motion: bool,
+
+    /// Use ECH and EL for runs of blank cells
+    pub erase: bool,
+
+    /// Use REP for runs of repeated characters
+    pub repeat: bool,
+
+    /// Move rows which shifted vertically with DECSTBM and SU/SD or IL/DL instead of repainting
+    /// them
+    pub scroll: bool,
+}
+
+impl Default for Optimizations {
+    fn default() -> Self {
+        Self {
+            cursor_motion: true,
+            erase: true,
+            repeat: false,
+            scroll: true,
+        }
+    }
+}
+
+/// Statistics about a single rendered frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RenderStats {
+    /// Number of cells which changed since the previous frame
+    pub cells: usize,
+
+    /// Number of bytes written
+    pub bytes: usize,
+
+    /// Number of bytes an unoptimized renderer would have written, moving the cursor absolutely
+    /// and printing every changed cell
+    pub naive_bytes: usize,
+
+    /// Whether existing rows were scrolled into their new position
+    pub scrolled: bool,
+}
+
+impl RenderStats {
+    /// Returns the number of bytes saved by optimizations
+    pub fn saved(&self) -> usize {
+        self.naive_bytes.saturating_sub(self.bytes)
+    }
+}
+
+/// Builds the output of a frame while tracking the cursor and pen of the terminal
+pub(crate) struct Emitter<'a> {
+    pub output: Vec<u8>,
+    next: &'a Buffer,
+    optimizations: Optimizations,
+    cursor: Option<Position>,
+    pen: Style,
+}
+
+impl<'a> Emitter<'a> {
+    pub fn new(next: &'a Buffer, optimizations: Optimizations) -> Self {
+        Self {
+            output: Vec::new(),
+            next,
+            optimizations,
+            cursor: None,
+            pen: Style::default(),
+        }
+    }
+
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.output.extend_from_slice(bytes);
+    }
+
+    fn set_pen(&mut self, style: &Style) {
+        let sequence = self.pen.diff(style);
+
+        self.output.extend_from_slice(sequence.as_bytes());
+        self.pen = *style;
+    }
+
+    /// Draws every changed cell, which must be sorted in drawing order
+    pub fn draw(&mut self, changes: &[(Position, &Cell)]) {
+        let mut idx = 0;
+
+        while idx < changes.len() {
+            let (position, cell) = changes[idx];
+
+            let covered = if self.optimizations.erase {
+                self.try_erase(position, cell, &changes[idx..])
+            } else {
+                None
+            };
+
+            let covered = covered.or_else(|| {
+                if self.optimizations.repeat {
+                    self.try_repeat(position, cell, &changes[idx..])
+                } else {
+                    None
+                }
+            });
+
+            let covered = covered.unwrap_or_else(|| {
+                self.move_to(position);
+                self.print(position, cell);
+                position.col + cell.width() as u16
+            });
+
+            idx += 1;
+
+            while idx < changes.len()
+                && changes[idx].0.row == position.row
+                && changes[idx].0.col < covered
+            {
+                idx += 1;
+            }
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.set_pen(&Style::default());
+    }
+
+    fn print(&mut self, position: Position, cell: &Cell) {
+        self.set_pen(cell.style());
+        self.output.extend_from_slice(cell.symbol().as_bytes());
+
+        let end = position.col + cell.width() as u16;
+
+        // Writing the last column leaves the cursor in a pending wrap state, which terminals
+        // handle differently
+        self.cursor = (end < self.next.width()).then_some(Position::new(position.row, end));
+    }
+
+    /// Returns the column right after the last change on `row` that starts before `end`
+    fn changed_until(row: u16, end: u16, changes: &[(Position, &Cell)]) -> Option<u16> {
+        changes
+            .iter()
+            .take_while(|(position, _)| position.row == row && position.col < end)
+            .last()
+            .map(|(position, cell)| position.col + cell.width() as u16)
+    }
+
+    /// Erases a run of blank cells with ECH or EL if that is cheaper than printing them. Returns
+    /// the column after the run
+    fn try_erase(
+        &mut self,
+        position: Position,
+        cell: &Cell,
+        changes: &[(Position, &Cell)],
+    ) -> Option<u16> {
+        // Erased cells take the default background, and no attributes, on every terminal
+        if cell.symbol() != " " || !cell.style().is_plain() {
+            return None;
+        }
+
+        let Position { row: y, col: x } = position;
+
+        let row = self.next.row(y);
+        let run = row[x as usize..]
+            .iter()
+            .take_while(|i| i.symbol() == " " && i.style().is_plain())
+            .count() as u16;
+
+        let last = Self::changed_until(y, x + run, changes)?;
+        let printed = (last - x) as usize;
+
+        let sequence = if x + run == self.next.width() {
+            "\x1b[K".to_string()
+        } else if run == 1 {
+            "\x1b[X".to_string()
+        } else {
+            format!("\x1b[{}X", run)
+        };
+
+        if sequence.len() >= printed {
+            return None;
+        }
+
+        self.move_to(position);
+        self.set_pen(cell.style());
+        self.output.extend_from_slice(sequence.as_bytes());
+
+        Some(x + run)
+    }
+
+    /// Prints a run of identical cells once followed by REP if that is cheaper than printing them.
+    /// Returns the column after the run
+    fn try_repeat(
+        &mut self,
+        position: Position,
+        cell: &Cell,
+        changes: &[(Position, &Cell)],
+    ) -> Option<u16> {
+        // REP repeats the last character written, which is only the whole grapheme if it is a
+        // single character
+        if cell.width() != 1 || cell.symbol().chars().count() != 1 {
+            return None;
+        }
+
+        let Position { row: y, col: x } = position;
+
+        let row = self.next.row(y);
+        let run = row[x as usize..].iter().take_while(|i| *i == cell).count() as u16;
+
+        let last = Self::changed_until(y, x + run, changes)?;
+        let count = last - x;
+
+        if count < 2 {
+            return None;
+        }
+
+        let sequence = format!("\x1b[{}b", count - 1);
+
+        if sequence.len() >= (count as usize - 1) * cell.symbol().len() {
+            return None;
+        }
+
+        self.move_to(position);
+        self.print(position, cell);
+        self.output.extend_from_slice(sequence.as_bytes());
+
+        self.cursor = (last < self.next.width()).then_some(Position::new(y, last));
+
+        Some(last)
+    }
+
+    /// Moves the cursor to `position` using the cheapest sequence
+    fn move_to(&mut self, position: Position) {
+        if self.cursor == Some(position) {
+            return;
+        }
+
+        let Position { row: y, col: x } = position;
+        let mut best = absolute(position);
+
+        if let (Some(cursor), true) = (self.cursor, self.optimizations.cursor_motion) {
+            let Position { row: cy, col: cx } = cursor;
+            let mut candidates = vec![
+                vertical(cy, y) + &self.horizontal(cx, x, y),
+                "\r".to_string() + &vertical(cy, y) + &self.horizontal(0, x, y),
+            ];
+
+            if y > cy {
+                candidates.push("\r\n".repeat((y - cy) as usize) + &self.horizontal(0, x, y));
+            }
+
+            for candidate in candidates {
+                if candidate.len() < best.len() {
+                    best = candidate;
+                }
+            }
+        }
+
+        self.output.extend_from_slice(best.as_bytes());
+        self.cursor = Some(position);
+    }
+
+    /// Returns the cheapest way to move from column `from` to column `to` on row `y`
+    fn horizontal(&self, from: u16, to: u16, y: u16) -> String {
+        if from == to {
+            return String::new();
+        }
+
+        if to == 0 {
+            return "\r".into();
+        }
+
+        let mut best = if to > from {
+            relative(to - from, 'C')
+        } else {
+            relative(from - to, 'D')
+        };
+
+        let column = format!("\x1b[{}G", to + 1);
+
+        if column.len() < best.len() {
+            best = column;
+        }
+
+        if to > from {
+            if let Some(reprint) = self.reprint(from, to, y) {
+                if reprint.len() < best.len() {
+                    best = reprint;
+                }
+            }
+        }
+
+        best
+    }
+
+    /// Returns the cells between `from` and `to` of the next frame, if they can be printed with
+    /// the current pen to move the cursor forward
+    fn reprint(&self, from: u16, to: u16, y: u16) -> Option<String> {
+        let row = self.next.row(y);
+        let mut reprint = String::new();
+        let mut column = from;
+
+        while column < to {
+            let cell = row.get(column as usize)?;
+
+            if cell.is_continuation() || *cell.style() != self.pen {
+                return None;
+            }
+
+            reprint.push_str(cell.symbol());
+            column += cell.width() as u16;
+        }
+
+        (column == to).then_some(reprint)
+    }
+}
+
+/// Returns CUP for a 0-based position, leaving out parameters that default to 1
+pub(crate) fn absolute(position: Position) -> String {
+    match (position.row, position.col) {
+        (0, 0) => "\x1b[H".into(),
+        (row, 0) => format!("\x1b[{}H", row + 1),
+        (row, col) => format!("\x1b[{};{}H", row + 1, col + 1),
+    }
+}
+
+/// Returns a vertical cursor movement between two rows
+fn vertical(from: u16, to: u16) -> String {
+    match to.cmp(&from) {
+        std::cmp::Ordering::Equal => String::new(),
+        std::cmp::Ordering::Greater => relative(to - from, 'B'),
+        std::cmp::Ordering::Less => relative(from - to, 'A'),
+    }
+}
+
+/// Returns a relative cursor movement, leaving out a count of 1
+fn relative(count: u16, direction: char) -> String {
+    if count == 1 {
+        format!("\x1b[{}", direction)
+    } else {
+        format!("\x1b[{}{}", count, direction)
+    }
+}
+
+/// Returns the number of bytes an unoptimized renderer writes for the changes: an absolute move
+/// before every cell not directly following the previous one, the style difference and the
+/// symbol, followed by a reset of the pen
+pub(crate) fn naive_cost(changes: &[(Position, &Cell)]) -> usize {
+    let mut cost = 0;
+    let mut pen = Style::default();
+    let mut cursor = None;
+
+    for (position, cell) in changes {
+        if cursor != Some(*position) {
+            cost += format!("\x1b[{};{}H", position.row + 1, position.col + 1).len();
+        }
+
+        cost += pen.diff(cell.style()).len() + cell.symbol().len();
+
+        pen = *cell.style();
+        cursor = Some(Position::new(
+            position.row,
+            position.col + cell.width() as u16,
+        ));
+    }
+
+    cost + pen.diff(&Style::default()).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{geometry::Size, render::Renderer};
+
+    /// Renders `previous` and then `next`, returning the output and statistics of the second frame
+    fn render(
+        optimizations: Optimizations,
+        previous: &[&str],
+        next: &[&str],
+    ) -> (String, RenderStats) {
+        let mut renderer = Renderer::new(Size::new(previous.len() as u16, 20));
+        renderer.set_optimizations(optimizations);
+
+        let mut draw = |lines: &[&str]| {
+            for (row, line) in lines.iter().enumerate() {
+                renderer.buffer_mut().set_string(
+                    Position::new(row as u16, 0),
+                    line,
+                    Style::default(),
+                );
+            }
+
+            let mut output = Vec::new();
+            let stats = renderer.render(&mut output).unwrap();
+
+            (String::from_utf8(output).unwrap(), stats)
+        };
+
+        draw(previous);
+        draw(next)
+    }
+
+    #[test]
+    fn short_hops_move_relatively() {
+        let o = Optimizations::default();
+
+        // The first move is absolute since the cursor position is unknown
+        let (output, _) = render(o, &["abcdefghij"], &["aBcdefghIj"]);
+        assert_eq!(output, "\x1b[1;2HB\x1b[6CI");
+
+        let (output, _) = render(o, &["abcdefghij", "x"], &["aBcdefghij", "xY"]);
+        assert_eq!(output, "\x1b[1;2HB\r\nxY");
+
+        let absolute = Optimizations {
+            cursor_motion: false,
+            ..o
+        };
+
+        let (output, _) = render(absolute, &["abcdefghij"], &["aBcdefghIj"]);
+        assert_eq!(output, "\x1b[1;2HB\x1b[1;9HI");
+    }
+
+    #[test]
+    fn blank_runs_are_erased() {
+        let o = Optimizations::default();
+
+        let (output, stats) = render(o, &["hello world"], &["hello"]);
+        assert_eq!(output, "\x1b[1;7H\x1b[K");
+        assert_eq!(stats.cells, 5);
+
+        let (output, _) = render(o, &["hello world  abc"], &["hello        abc"]);
+        assert_eq!(output, "\x1b[1;7H\x1b[7X");
+
+        let (output, _) = render(
+            Optimizations { erase: false, ..o },
+            &["hello world"],
+            &["hello"],
+        );
+        assert_eq!(output, "\x1b[1;7H     ");
+    }
+
+    #[test]
+    fn repeated_characters_use_rep() {
+        let o = Optimizations::default();
+        let line = "a".repeat(20);
+
+        let (output, _) = render(o, &[""], &[&line]);
+        assert_eq!(output, format!("\x1b[H{}", line));
+
+        let (output, _) = render(Optimizations { repeat: true, ..o }, &[""], &[&line]);
+        assert_eq!(output, "\x1b[Ha\x1b[19b");
+
+        // Only the combining accent would be repeated
+        let line = "e\u{301}".repeat(20);
+
+        let (output, _) = render(Optimizations { repeat: true, ..o }, &[""], &[&line]);
+        assert_eq!(output, format!("\x1b[H{}", line));
+    }
+
+    #[test]
+    fn stats_count_saved_bytes() {
+        let o = Optimizations {
+            repeat: true,
+            ..Optimizations::default()
+        };
+
+        let (output, stats) = render(o, &[""], &[&"a".repeat(20)]);
+
+        assert_eq!(
+            stats,
+            RenderStats {
+                cells: 20,
+                bytes: output.len(),
+                naive_bytes: 26,
+                scrolled: false,
+            }
+        );
+        assert_eq!(stats.saved(), 17);
+
+        let stats = RenderStats {
+            bytes: 10,
+            naive_bytes: 4,
+            ..stats
+        };
+        assert_eq!(stats.saved(), 0);
+    }
+}
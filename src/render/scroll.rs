@@ -0,0 +1,240 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use crate::geometry::Position;
+
+use super::{optimizer::absolute, Buffer};
+
+/// Rows from `top` to `bottom` (inclusive) moved up or down by `lines`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Shift {
+    top: u16,
+    bottom: u16,
+    lines: u16,
+    up: bool,
+}
+
+impl Shift {
+    /// Finds the shift that lines up the most changed rows of `next` with rows of `previous`
+    pub fn detect(previous: &Buffer, next: &Buffer) -> Option<Self> {
+        if previous.size() != next.size() {
+            return None;
+        }
+
+        let before = hash_rows(previous);
+        let after = hash_rows(next);
+        let height = after.len();
+
+        let mut best: Option<(usize, Shift)> = None;
+
+        for lines in 1..height {
+            for up in [true, false] {
+                let source = |row: usize| {
+                    if up {
+                        Some(row + lines).filter(|source| *source < height)
+                    } else {
+                        row.checked_sub(lines)
+                    }
+                };
+
+                let mut start = 0;
+                let mut gained = 0;
+
+                for row in 0..=height {
+                    let matched =
+                        row < height && source(row).is_some_and(|i| before[i] == after[row]);
+
+                    if matched {
+                        if after[row] != before[row] {
+                            gained += 1;
+                        }
+
+                        continue;
+                    }
+
+                    let improves = best.map_or(gained > 0, |(score, _)| gained > score);
+
+                    if improves {
+                        let (top, bottom) = if up {
+                            (start, row - 1 + lines)
+                        } else {
+                            (start - lines, row - 1)
+                        };
+
+                        let shift = Shift {
+                            top: top as u16,
+                            bottom: bottom as u16,
+                            lines: lines as u16,
+                            up,
+                        };
+
+                        best = Some((gained, shift));
+                    }
+
+                    start = row + 1;
+                    gained = 0;
+                }
+            }
+        }
+
+        best.map(|(_, shift)| shift)
+    }
+
+    /// Moves the rows of `buffer` the way the terminal does when receiving `sequence`
+    pub fn apply(&self, buffer: &mut Buffer) {
+        if self.up {
+            buffer.scroll_up(self.top, self.bottom, self.lines);
+        } else {
+            buffer.scroll_down(self.top, self.bottom, self.lines);
+        }
+    }
+
+    /// Returns the sequence shifting the rows on a screen of the given height. Regions reaching
+    /// the bottom of the screen use DL/IL, other regions are scrolled within DECSTBM margins with
+    /// SU/SD. The cursor position is undefined afterwards
+    pub fn sequence(&self, height: u16) -> String {
+        if self.bottom + 1 == height {
+            let code = if self.up { 'M' } else { 'L' };
+
+            format!(
+                "{}\x1b[{}{}",
+                absolute(Position::new(self.top, 0)),
+                self.lines,
+                code
+            )
+        } else {
+            let code = if self.up { 'S' } else { 'T' };
+
+            format!(
+                "\x1b[{};{}r\x1b[{}{}\x1b[r",
+                self.top + 1,
+                self.bottom + 1,
+                self.lines,
+                code
+            )
+        }
+    }
+}
+
+fn hash_rows(buffer: &Buffer) -> Vec<u64> {
+    (0..buffer.height())
+        .map(|y| {
+            let mut hasher = DefaultHasher::new();
+            buffer.row(y).hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        geometry::Size,
+        render::{RenderStats, Renderer},
+        style::Style,
+    };
+
+    fn buffer(lines: &[&str]) -> Buffer {
+        let mut buffer = Buffer::new(Size::new(lines.len() as u16, 10));
+
+        for (row, line) in lines.iter().enumerate() {
+            buffer.set_string(Position::new(row as u16, 0), line, Style::default());
+        }
+
+        buffer
+    }
+
+    /// Renders `previous` and then `next`, returning the output and statistics of the second frame
+    fn render(previous: &[&str], next: &[&str]) -> (String, RenderStats) {
+        let mut renderer = Renderer::new(Size::new(previous.len() as u16, 10));
+
+        let mut draw = |lines: &[&str]| {
+            *renderer.buffer_mut() = buffer(lines);
+
+            let mut output = Vec::new();
+            let stats = renderer.render(&mut output).unwrap();
+
+            (String::from_utf8(output).unwrap(), stats)
+        };
+
+        draw(previous);
+        draw(next)
+    }
+
+    const LINES: [&str; 6] = ["one", "two", "three", "four", "five", "six"];
+
+    #[test]
+    fn detects_shifted_rows() {
+        let previous = buffer(&LINES);
+
+        let next = buffer(&["two", "three", "four", "five", "six", ""]);
+        let shift = Shift::detect(&previous, &next).unwrap();
+        assert_eq!(
+            shift,
+            Shift {
+                top: 0,
+                bottom: 5,
+                lines: 1,
+                up: true,
+            }
+        );
+
+        let mut shifted = previous.clone();
+        shift.apply(&mut shifted);
+        assert_eq!(shifted, next);
+
+        let next = buffer(&["one", "", "", "two", "three", "four"]);
+        assert_eq!(
+            Shift::detect(&previous, &next),
+            Some(Shift {
+                top: 1,
+                bottom: 5,
+                lines: 2,
+                up: false,
+            })
+        );
+    }
+
+    #[test]
+    fn no_shift() {
+        let previous = buffer(&LINES);
+
+        assert_eq!(Shift::detect(&previous, &previous), None);
+        assert_eq!(Shift::detect(&previous, &buffer(&["a"; 6])), None);
+        assert_eq!(Shift::detect(&previous, &buffer(&LINES[..5])), None);
+
+        let (output, stats) = render(&LINES, &["one", "two", "THREE", "four", "five", "six"]);
+        assert_eq!(output, "\x1b[3HTHREE");
+        assert!(!stats.scrolled);
+    }
+
+    #[test]
+    fn shifts_reaching_the_bottom_use_dl_and_il() {
+        let (output, stats) = render(&LINES, &["two", "three", "four", "five", "six", "seven"]);
+        assert_eq!(output, "\x1b[H\x1b[1M\x1b[6Hseven");
+        assert!(stats.scrolled);
+
+        let (output, stats) = render(&LINES, &["one", "zero", "two", "three", "four", "five"]);
+        assert_eq!(output, "\x1b[2H\x1b[1L\x1b[2Hzero");
+        assert!(stats.scrolled);
+    }
+
+    #[test]
+    fn other_shifts_use_scroll_margins() {
+        let previous = ["one", "two", "three", "four", "five", "status"];
+
+        let (output, stats) = render(
+            &previous,
+            &["two", "three", "four", "five", "six", "status"],
+        );
+        assert_eq!(output, "\x1b[1;5r\x1b[1S\x1b[r\x1b[5Hsix");
+        assert!(stats.scrolled);
+
+        let (output, stats) = render(
+            &previous,
+            &["zero", "one", "two", "three", "four", "status"],
+        );
+        assert_eq!(output, "\x1b[1;5r\x1b[1T\x1b[r\x1b[Hzero");
+        assert!(stats.scrolled);
+    }
+}
@@ -0,0 +1,431 @@
+use unicode_width::UnicodeWidthChar;
+
+use super::{Attribute, Color, Rgb, Span, Style, Text};
+
+/// A piece of ANSI styled input
+enum Token<'a> {
+    /// Printable text, which can contain other control characters such as newlines
+    Text(&'a [u8]),
+
+    /// The parameters of an SGR sequence, `\x1b[...m`
+    Sgr(&'a [u8]),
+
+    /// Any other escape sequence or control string, such as cursor movement or OSC 8 hyperlinks
+    Control,
+}
+
+/// Splits input into text, SGR sequences and other escape sequences. Incomplete sequences at the
+/// end of the input are treated as control sequences and dropped
+struct Tokens<'a> {
+    input: &'a [u8],
+    idx: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        Self { input, idx: 0 }
+    }
+
+    /// Advances past a control string terminated by BEL or ST, `\x1b\\`
+    fn skip_string(&mut self) {
+        while self.idx < self.input.len() {
+            match self.input[self.idx] {
+                b'\x07' => {
+                    self.idx += 1;
+                    return;
+                }
+                b'\x1b' if self.input.get(self.idx + 1) == Some(&b'\\') => {
+                    self.idx += 2;
+                    return;
+                }
+                _ => self.idx += 1,
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.idx;
+
+        if start >= self.input.len() {
+            return None;
+        }
+
+        if self.input[start] != b'\x1b' {
+            let len = self.input[start..]
+                .iter()
+                .position(|i| *i == b'\x1b')
+                .unwrap_or(self.input.len() - start);
+
+            self.idx += len;
+
+            return Some(Token::Text(&self.input[start..self.idx]));
+        }
+
+        self.idx += 1;
+
+        let Some(&kind) = self.input.get(self.idx) else {
+            return Some(Token::Control);
+        };
+
+        self.idx += 1;
+
+        match kind {
+            b'[' => {
+                let params = self.idx;
+
+                // Parameter and intermediate bytes followed by a single final byte
+                while self
+                    .input
+                    .get(self.idx)
+                    .is_some_and(|i| (0x20..=0x3f).contains(i))
+                {
+                    self.idx += 1;
+                }
+
+                let Some(&last) = self.input.get(self.idx) else {
+                    return Some(Token::Control);
+                };
+
+                self.idx += 1;
+
+                if last == b'm' {
+                    Some(Token::Sgr(&self.input[params..self.idx - 1]))
+                } else {
+                    Some(Token::Control)
+                }
+            }
+            b']' | b'P' | b'X' | b'^' | b'_' => {
+                self.skip_string();
+
+                Some(Token::Control)
+            }
+            _ => {
+                // Intermediate bytes of sequences such as charset selection, "\x1b(B"
+                if (0x20..=0x2f).contains(&kind) {
+                    while self
+                        .input
+                        .get(self.idx)
+                        .is_some_and(|i| (0x20..=0x2f).contains(i))
+                    {
+                        self.idx += 1;
+                    }
+
+                    self.idx = (self.idx + 1).min(self.input.len());
+                }
+
+                Some(Token::Control)
+            }
+        }
+    }
+}
+
+/// Parses text containing ANSI escape sequences, such as the colored output of another program,
+/// into styled spans. SGR sequences are applied to the style of the following text and every
+/// other escape sequence is removed. Invalid UTF-8 is replaced with `U+FFFD`
+pub fn parse_ansi(input: &[u8]) -> Text {
+    let mut text = Text::new();
+    let mut style = Style::default();
+
+    for token in Tokens::new(input) {
+        match token {
+            Token::Text(content) => {
+                text.push(Span::new(String::from_utf8_lossy(content), style));
+            }
+            Token::Sgr(params) => apply_sgr(&mut style, &String::from_utf8_lossy(params)),
+            Token::Control => {}
+        }
+    }
+
+    text
+}
+
+/// Removes every escape sequence and control string from `input`, leaving the visible text
+pub fn strip_ansi(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+
+    for token in Tokens::new(input.as_bytes()) {
+        if let Token::Text(content) = token {
+            output.push_str(&String::from_utf8_lossy(content));
+        }
+    }
+
+    output
+}
+
+/// Returns the number of terminal columns `input` occupies, ignoring escape sequences and control
+/// characters. Wide characters such as CJK count as two columns. Newlines are not treated
+/// specially, so this is the sum of the widths of every line
+pub fn visible_width(input: &str) -> usize {
+    strip_ansi(input)
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| c.width().unwrap_or(0))
+        .sum()
+}
+
+/// Applies the parameters of an SGR sequence to `style`
+pub(crate) fn apply_sgr(style: &mut Style, params: &str) {
+    let groups: Vec<Vec<&str>> = params.split(';').map(|i| i.split(':').collect()).collect();
+
+    let mut idx = 0;
+
+    while idx < groups.len() {
+        let group = &groups[idx];
+        idx += 1;
+
+        // An empty parameter means 0, invalid parameters are ignored
+        let code = match group[0] {
+            "" => 0,
+            param => match param.parse::<u16>() {
+                Ok(code) => code,
+                Err(_) => continue,
+            },
+        };
+
+        match code {
+            0 => *style = Style::default(),
+
+            4 if group.len() > 1 => {
+                style.attributes = remove_underlines(style.attributes.iter());
+
+                match group[1] {
+                    "1" => style.attributes.insert(Attribute::Underline),
+                    "2" => style.attributes.insert(Attribute::DoubleUnderline),
+                    "3" => style.attributes.insert(Attribute::CurlyUnderline),
+                    "4" => style.attributes.insert(Attribute::DottedUnderline),
+                    "5" => style.attributes.insert(Attribute::DashedUnderline),
+                    _ => {}
+                }
+            }
+
+            1 => style.attributes.insert(Attribute::Bold),
+            2 => style.attributes.insert(Attribute::Dim),
+            3 => style.attributes.insert(Attribute::Italic),
+            4 => {
+                style.attributes = remove_underlines(style.attributes.iter());
+                style.attributes.insert(Attribute::Underline);
+            }
+            5 => style.attributes.insert(Attribute::SlowBlink),
+            6 => style.attributes.insert(Attribute::RapidBlink),
+            7 => style.attributes.insert(Attribute::Reverse),
+            8 => style.attributes.insert(Attribute::Hidden),
+            9 => style.attributes.insert(Attribute::Strikethrough),
+            21 => {
+                style.attributes = remove_underlines(style.attributes.iter());
+                style.attributes.insert(Attribute::DoubleUnderline);
+            }
+            53 => style.attributes.insert(Attribute::Overline),
+
+            22..=29 | 55 => {
+                for attribute in Attribute::ALL {
+                    if attribute.reset_code() == code.to_string() {
+                        style.attributes.remove(attribute);
+                    }
+                }
+            }
+
+            30..=37 => style.fg = Some(Color::NAMED[(code - 30) as usize]),
+            90..=97 => style.fg = Some(Color::NAMED[(code - 90 + 8) as usize]),
+            39 => style.fg = None,
+
+            40..=47 => style.bg = Some(Color::NAMED[(code - 40) as usize]),
+            100..=107 => style.bg = Some(Color::NAMED[(code - 100 + 8) as usize]),
+            49 => style.bg = None,
+
+            59 => style.underline_color = None,
+
+            38 | 48 | 58 => {
+                let color = if group.len() > 1 {
+                    parse_extended_color(&group[1..])
+                } else {
+                    // Semicolon form, the color parameters are separate groups
+                    let rest: Vec<&str> = groups[idx..].iter().map(|i| i[0]).collect();
+                    let (color, used) = parse_extended_color_separated(&rest);
+
+                    idx += used;
+
+                    color
+                };
+
+                let target = match code {
+                    38 => &mut style.fg,
+                    48 => &mut style.bg,
+                    _ => &mut style.underline_color,
+                };
+
+                // Underline colors have no named form, so named colors are written as their
+                // palette index
+                let color = match color {
+                    Some(Color::Extended(index)) if code == 58 && index < 16 => {
+                        Some(Color::NAMED[index as usize])
+                    }
+                    color => color,
+                };
+
+                if color.is_some() {
+                    *target = color;
+                }
+            }
+
+            _ => {}
+        }
+    }
+}
+
+fn remove_underlines(attributes: impl Iterator<Item = Attribute>) -> super::Attributes {
+    attributes
+        .filter(|i| i.reset_code() != Attribute::Underline.reset_code())
+        .collect()
+}
+
+/// Parses the colon form, `5:n`, `2:r:g:b` or `2::r:g:b` with an empty color space id
+fn parse_extended_color(params: &[&str]) -> Option<Color> {
+    match params {
+        ["5", index] => index.parse::<u8>().ok().map(Color::Extended),
+        ["2", _, r, g, b, ..] | ["2", r, g, b] => parse_rgb(r, g, b),
+        _ => None,
+    }
+}
+
+/// Parses the semicolon form, `5;n` or `2;r;g;b`, returning the number of parameters used
+fn parse_extended_color_separated(params: &[&str]) -> (Option<Color>, usize) {
+    match params {
+        ["5", index, ..] => (index.parse::<u8>().ok().map(Color::Extended), 2),
+        ["2", r, g, b, ..] => (parse_rgb(r, g, b), 4),
+        _ => (None, params.len().min(1)),
+    }
+}
+
+fn parse_rgb(r: &str, g: &str, b: &str) -> Option<Color> {
+    Some(Color::Rgb(Rgb::new(
+        r.parse().ok()?,
+        g.parse().ok()?,
+        b.parse().ok()?,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses the SGR sequence of `style` back into a style
+    fn round_trip(style: Style) -> Style {
+        parse_ansi(format!("{}x", style.sequence()).as_bytes()).spans[0].style
+    }
+
+    #[test]
+    fn underline_colors_round_trip() {
+        for color in Color::NAMED {
+            let style = Style::new()
+                .with_attribute(Attribute::Underline)
+                .with_underline_color(color);
+
+            assert_eq!(round_trip(style), style);
+        }
+
+        let style = Style::new().with_underline_color(Color::Extended(200));
+        assert_eq!(round_trip(style), style);
+
+        let style = Style::new().with_underline_color(Rgb::new(1, 2, 3));
+        assert_eq!(round_trip(style), style);
+    }
+
+    #[test]
+    fn invalid_parameters_are_ignored() {
+        let mut style = Style::new();
+
+        apply_sgr(&mut style, "1;99999;x;3");
+        assert_eq!(
+            style,
+            Style::new()
+                .with_attribute(Attribute::Bold)
+                .with_attribute(Attribute::Italic)
+        );
+
+        apply_sgr(&mut style, "99999");
+        assert_eq!(
+            style,
+            Style::new()
+                .with_attribute(Attribute::Bold)
+                .with_attribute(Attribute::Italic)
+        );
+
+        // Only an empty parameter resets
+        apply_sgr(&mut style, ";4");
+        assert_eq!(style, Style::new().with_attribute(Attribute::Underline));
+
+        let text = parse_ansi(b"\x1b[1m\x1b[99999ma");
+        assert_eq!(
+            text.spans[0].style,
+            Style::new().with_attribute(Attribute::Bold)
+        );
+    }
+
+    #[test]
+    fn extended_colors_in_both_forms() {
+        let expected = Style::new()
+            .with_fg(Color::Extended(208))
+            .with_bg(Rgb::new(1, 2, 3))
+            .with_underline_color(Rgb::new(4, 5, 6));
+
+        let inputs: [&[u8]; 3] = [
+            b"\x1b[38;5;208;48;2;1;2;3;58;2;4;5;6mx",
+            b"\x1b[38:5:208;48:2:1:2:3;58:2::4:5:6mx",
+            b"\x1b[38:5:208m\x1b[48:2::1:2:3m\x1b[58;2;4;5;6mx",
+        ];
+
+        for input in inputs {
+            assert_eq!(parse_ansi(input).spans[0].style, expected);
+        }
+    }
+
+    #[test]
+    fn underline_styles() {
+        let text = parse_ansi(b"\x1b[4:3ma\x1b[4:1mb\x1b[4:0mc");
+
+        assert_eq!(
+            text.spans[0].style,
+            Style::new().with_attribute(Attribute::CurlyUnderline)
+        );
+        assert_eq!(
+            text.spans[1].style,
+            Style::new().with_attribute(Attribute::Underline)
+        );
+        assert_eq!(text.spans[2].style, Style::new());
+    }
+
+    #[test]
+    fn hyperlinks_are_removed() {
+        let bel = "\x1b]8;;https://example.com\x07link\x1b]8;;\x07 text";
+        let st = "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x1b\\ text";
+
+        for input in [bel, st] {
+            assert_eq!(strip_ansi(input), "link text");
+            assert_eq!(visible_width(input), 9);
+            assert_eq!(parse_ansi(input.as_bytes()).spans[0].content, "link text");
+        }
+    }
+
+    #[test]
+    fn truncated_sequences_are_dropped() {
+        assert_eq!(strip_ansi("text\x1b"), "text");
+        assert_eq!(strip_ansi("text\x1b[1;3"), "text");
+        assert_eq!(strip_ansi("text\x1b]8;;https://example.com"), "text");
+
+        let text = parse_ansi(b"a\x1b[31");
+
+        assert_eq!(text.spans.len(), 1);
+        assert_eq!(text.spans[0].style, Style::new());
+    }
+
+    #[test]
+    fn wide_characters() {
+        assert_eq!(visible_width("\x1b[31m\u{4e2d}\u{6587}\x1b[0m!"), 5);
+        assert_eq!(visible_width("e\u{301}"), 1);
+        assert_eq!(strip_ansi("\x1b[1m\u{4e2d}\x1b[0m"), "\u{4e2d}");
+    }
+}
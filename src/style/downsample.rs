@@ -0,0 +1,129 @@
+use super::{Color, Rgb};
+
+/// The amount of colors a terminal is able to display
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ColorLevel {
+    /// Colors should not be written at all, for example when `NO_COLOR` is set
+    None,
+
+    /// The 16 named colors
+    Ansi16,
+
+    /// The 256 color palette
+    Ansi256,
+
+    /// 24-bit truecolor
+    TrueColor,
+}
+
+/// The default xterm values of the 16 named colors, used when matching against them. The actual
+/// values depend on the palette of the user's terminal.
+const NAMED_RGB: [Rgb; 16] = [
+    Rgb::new(0, 0, 0),
+    Rgb::new(205, 0, 0),
+    Rgb::new(0, 205, 0),
+    Rgb::new(205, 205, 0),
+    Rgb::new(0, 0, 238),
+    Rgb::new(205, 0, 205),
+    Rgb::new(0, 205, 205),
+    Rgb::new(229, 229, 229),
+    Rgb::new(127, 127, 127),
+    Rgb::new(255, 0, 0),
+    Rgb::new(0, 255, 0),
+    Rgb::new(255, 255, 0),
+    Rgb::new(92, 92, 255),
+    Rgb::new(255, 0, 255),
+    Rgb::new(0, 255, 255),
+    Rgb::new(255, 255, 255),
+];
+
+/// The channel values of the 6x6x6 color cube at indices `16..=231`
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl Rgb {
+    /// Returns the value of an index into the xterm 256 color palette. The first 16 entries use
+    /// the default xterm values of the named colors
+    pub fn from_ansi256(index: u8) -> Self {
+        match index {
+            0..=15 => NAMED_RGB[index as usize],
+            16..=231 => {
+                let index = index - 16;
+
+                Rgb::new(
+                    CUBE_LEVELS[(index / 36) as usize],
+                    CUBE_LEVELS[(index / 6 % 6) as usize],
+                    CUBE_LEVELS[(index % 6) as usize],
+                )
+            }
+            232..=255 => {
+                let level = 8 + (index - 232) * 10;
+
+                Rgb::new(level, level, level)
+            }
+        }
+    }
+
+    /// Returns the index of the closest color in the xterm 256 color palette. Only the color cube
+    /// and the grayscale ramp are considered since the first 16 entries vary between terminals
+    pub fn to_ansi256(&self) -> u8 {
+        let nearest_level = |value: u8| {
+            (0..CUBE_LEVELS.len())
+                .min_by_key(|i| CUBE_LEVELS[*i].abs_diff(value))
+                .unwrap_or_default() as u8
+        };
+
+        let cube =
+            16 + 36 * nearest_level(self.r) + 6 * nearest_level(self.g) + nearest_level(self.b);
+
+        let average = (self.r as u16 + self.g as u16 + self.b as u16) / 3;
+        let gray = 232 + ((average.saturating_sub(3)) / 10).min(23) as u8;
+
+        if self.distance(&Rgb::from_ansi256(gray)) < self.distance(&Rgb::from_ansi256(cube)) {
+            gray
+        } else {
+            cube
+        }
+    }
+
+    /// Returns the closest of the 16 named colors
+    pub fn to_named(&self) -> Color {
+        let index = (0..NAMED_RGB.len())
+            .min_by_key(|i| self.distance(&NAMED_RGB[*i]))
+            .unwrap_or_default();
+
+        Color::NAMED[index]
+    }
+
+    /// Returns the perceptual distance between two colors. This uses the "redmean" weighted
+    /// euclidean distance, which accounts for the eye being more sensitive to green and less
+    /// sensitive to blue, at a fraction of the cost of a full color space conversion. The result
+    /// is only meaningful for comparisons
+    pub fn distance(&self, other: &Rgb) -> u32 {
+        let mean = (self.r as i32 + other.r as i32) / 2;
+
+        let r = self.r as i32 - other.r as i32;
+        let g = self.g as i32 - other.g as i32;
+        let b = self.b as i32 - other.b as i32;
+
+        ((((512 + mean) * r * r) >> 8) + 4 * g * g + (((767 - mean) * b * b) >> 8)) as u32
+    }
+}
+
+impl Color {
+    /// Converts this color into one that can be displayed at `level`. Colors which are already
+    /// supported are returned unchanged and `None` is returned for `ColorLevel::None`
+    pub fn downsample(self, level: ColorLevel) -> Option<Color> {
+        match (self, level) {
+            (_, ColorLevel::None) => None,
+
+            (Color::Rgb(rgb), ColorLevel::Ansi256) => Some(Color::Extended(rgb.to_ansi256())),
+            (Color::Rgb(rgb), ColorLevel::Ansi16) => Some(rgb.to_named()),
+
+            (Color::Extended(index), ColorLevel::Ansi16) => {
+                Some(Rgb::from_ansi256(index).to_named())
+            }
+
+            (color, _) => Some(color),
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use std::{fmt::Display, io::Write, str::FromStr};
+
+use unicode_width::UnicodeWidthStr;
+
+use super::{colors_enabled, stylize::display_capabilities, Attribute, Color, Style};
+
+/// A run of text sharing a single style
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    pub content: String,
+    pub style: Style,
+}
+
+impl Span {
+    pub fn new(content: impl Into<String>, style: Style) -> Self {
+        Self {
+            content: content.into(),
+            style,
+        }
+    }
+
+    /// Returns the number of terminal columns the content occupies
+    pub fn width(&self) -> usize {
+        self.content.width()
+    }
+}
+
+/// A sequence of styled spans, usually created from markup such as
+/// `"[bold red]error[/]: file not found"`.
+///
+/// A tag contains a space separated list of attributes and colors. Colors are foreground colors
+/// unless preceded by `on`. Colors use any format accepted by `Color::from_str`, for example
+/// `red`, `bright_red`, `#ff8800`, `color(202)` or `SlateGray`. Bare palette indices such as `202`
+/// are rejected since `[0]` is more likely to be text than a tag. `[/]` closes the most recently
+/// opened tag and `[/bold red]` closes a tag by repeating its contents. Tags which are still open
+/// at the end are closed implicitly. Literal brackets are written as `\[` and `\]`, and a literal
+/// backslash as `\\`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Text {
+    pub spans: Vec<Span>,
+}
+
+impl Text {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses markup into styled spans, see `Text` for the syntax
+    pub fn parse(markup: &str) -> Result<Self, MarkupError> {
+        parse_markup(markup)
+    }
+
+    /// Appends a span, merging it with the last span if both share a style
+    pub fn push(&mut self, span: Span) {
+        if span.content.is_empty() {
+            return;
+        }
+
+        match self.spans.last_mut() {
+            Some(last) if last.style == span.style => last.content.push_str(&span.content),
+            _ => self.spans.push(span),
+        }
+    }
+
+    /// Returns the number of terminal columns the text occupies
+    pub fn width(&self) -> usize {
+        self.spans.iter().map(|i| i.width()).sum()
+    }
+
+    /// Returns the text without any styling
+    pub fn plain(&self) -> String {
+        self.spans.iter().map(|i| i.content.as_str()).collect()
+    }
+
+    /// Writes the text, changing only the parameters that differ between spans and resetting the
+    /// pen at the end. Unlike the `Display` implementation this ignores `colors_enabled` and writes
+    /// colors without downsampling them
+    pub fn write_to<W>(&self, output: &mut W) -> std::io::Result<()>
+    where
+        W: Write,
+    {
+        let mut current = Style::default();
+
+        for span in self.spans.iter() {
+            output.write_all(current.diff(&span.style).as_bytes())?;
+            output.write_all(span.content.as_bytes())?;
+
+            current = span.style;
+        }
+
+        output.write_all(current.diff(&Style::default()).as_bytes())
+    }
+}
+
+impl Display for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !colors_enabled() {
+            return f.write_str(&self.plain());
+        }
+
+        let capabilities = display_capabilities();
+        let mut current = Style::default();
+
+        for span in self.spans.iter() {
+            let style = span.style.adapt(&capabilities);
+
+            f.write_str(&current.diff(&style))?;
+            f.write_str(&span.content)?;
+
+            current = style;
+        }
+
+        f.write_str(&current.diff(&Style::default()))
+    }
+}
+
+impl FromStr for Text {
+    type Err = MarkupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Parses a style description such as `"bold red on #202020"`, the same syntax used inside
+/// markup tags
+impl FromStr for Style {
+    type Err = MarkupError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_style(s, 0)
+    }
+}
+
+/// Parses markup into styled spans, see `Text` for the syntax
+pub fn markup(markup: &str) -> Result<Text, MarkupError> {
+    Text::parse(markup)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MarkupErrorKind {
+    /// A `[` without a matching `]`
+    UnterminatedTag,
+
+    /// A tag without any contents, `[]`
+    EmptyTag,
+
+    /// A closing tag while no tag is open
+    UnexpectedClose,
+
+    /// A closing tag which does not match the most recently opened tag
+    MismatchedClose { expected: String },
+
+    /// A word which is neither an attribute nor a color
+    UnknownStyle(String),
+
+    /// `on` without a following color
+    MissingBackground,
+}
+
+/// An error in markup, with the byte offset at which it occurred
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MarkupError {
+    pub position: usize,
+    pub kind: MarkupErrorKind,
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            MarkupErrorKind::UnterminatedTag => write!(f, "unterminated tag")?,
+            MarkupErrorKind::EmptyTag => write!(f, "empty tag")?,
+            MarkupErrorKind::UnexpectedClose => write!(f, "closing tag without an open tag")?,
+            MarkupErrorKind::MismatchedClose { expected } => {
+                write!(f, "closing tag does not match open tag \"{}\"", expected)?
+            }
+            MarkupErrorKind::UnknownStyle(word) => write!(f, "unknown style \"{}\"", word)?,
+            MarkupErrorKind::MissingBackground => write!(f, "expected a color after \"on\"")?,
+        }
+
+        write!(f, " at position {}", self.position)
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+fn parse_markup(markup: &str) -> Result<Text, MarkupError> {
+    let mut text = Text::new();
+
+    // Open tags with their contents and the style they produce
+    let mut stack: Vec<(&str, Style)> = Vec::new();
+    let mut content = String::new();
+
+    let current = |stack: &Vec<(&str, Style)>| stack.last().map(|i| i.1).unwrap_or_default();
+
+    let mut chars = markup.char_indices().peekable();
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => match chars.peek() {
+                Some((_, escaped @ ('[' | ']' | '\\'))) => {
+                    content.push(*escaped);
+                    chars.next();
+                }
+                _ => content.push('\\'),
+            },
+            '[' => {
+                let Some(len) = find_tag_end(&markup[idx..]) else {
+                    return Err(MarkupError {
+                        position: idx,
+                        kind: MarkupErrorKind::UnterminatedTag,
+                    });
+                };
+
+                let tag = &markup[idx + 1..idx + len];
+
+                text.push(Span::new(std::mem::take(&mut content), current(&stack)));
+
+                if let Some(closing) = tag.strip_prefix('/') {
+                    let Some((open, _)) = stack.pop() else {
+                        return Err(MarkupError {
+                            position: idx,
+                            kind: MarkupErrorKind::UnexpectedClose,
+                        });
+                    };
+
+                    if !closing.trim().is_empty() && closing.trim() != open.trim() {
+                        return Err(MarkupError {
+                            position: idx,
+                            kind: MarkupErrorKind::MismatchedClose {
+                                expected: open.trim().to_string(),
+                            },
+                        });
+                    }
+                } else {
+                    if tag.trim().is_empty() {
+                        return Err(MarkupError {
+                            position: idx,
+                            kind: MarkupErrorKind::EmptyTag,
+                        });
+                    }
+
+                    let style = current(&stack).patch(parse_style(tag, idx + 1)?);
+
+                    stack.push((tag, style));
+                }
+
+                while chars.peek().is_some_and(|(i, _)| *i <= idx + len) {
+                    chars.next();
+                }
+            }
+            c => content.push(c),
+        }
+    }
+
+    text.push(Span::new(content, current(&stack)));
+
+    Ok(text)
+}
+
+/// Parses a style description. `offset` is the position of `spec` within the markup and is only
+/// used for error positions
+fn parse_style(spec: &str, offset: usize) -> Result<Style, MarkupError> {
+    let mut style = Style::default();
+
+    let mut words = split_words(spec)
+        .into_iter()
+        .map(|(position, word)| (offset + position, word));
+
+    while let Some((position, word)) = words.next() {
+        if word == "on" {
+            let Some((position, word)) = words.next() else {
+                return Err(MarkupError {
+                    position,
+                    kind: MarkupErrorKind::MissingBackground,
+                });
+            };
+
+            style.bg = Some(parse_color(word, position)?);
+
+            continue;
+        }
+
+        if let Some(attribute) = parse_attribute(word) {
+            style.attributes.insert(attribute);
+            continue;
+        }
+
+        style.fg = Some(parse_color(word, position)?);
+    }
+
+    Ok(style)
+}
+
+/// Parses a color word. Bare palette indices are rejected so that text such as `[0]` is reported
+/// instead of styling what follows, palette colors are written as `color(n)`
+fn parse_color(word: &str, position: usize) -> Result<Color, MarkupError> {
+    let error = || MarkupError {
+        position,
+        kind: MarkupErrorKind::UnknownStyle(word.to_string()),
+    };
+
+    if word.bytes().all(|i| i.is_ascii_digit()) {
+        return Err(error());
+    }
+
+    word.parse::<Color>().map_err(|_| error())
+}
+
+/// Returns the offset of the `]` closing the tag at the start of `markup`, skipping escaped
+/// characters the same way as text outside of tags
+fn find_tag_end(markup: &str) -> Option<usize> {
+    let mut chars = markup.char_indices().skip(1);
+
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            ']' => return Some(idx),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Splits a style description into words with their offsets. Spaces inside parentheses, such as
+/// in `rgb(1, 2, 3)`, do not split words
+fn split_words(spec: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut depth = 0usize;
+
+    for (idx, c) in spec.char_indices() {
+        match c {
+            ' ' if depth == 0 => {
+                if let Some(start) = start.take() {
+                    words.push((start, &spec[start..idx]));
+                }
+
+                continue;
+            }
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+
+        start.get_or_insert(idx);
+    }
+
+    if let Some(start) = start {
+        words.push((start, &spec[start..]));
+    }
+
+    words
+}
+
+fn parse_attribute(word: &str) -> Option<Attribute> {
+    let attribute = match word {
+        "bold" | "b" => Attribute::Bold,
+        "dim" => Attribute::Dim,
+        "italic" | "i" => Attribute::Italic,
+        "underline" | "u" => Attribute::Underline,
+        "blink" => Attribute::SlowBlink,
+        "rapid_blink" => Attribute::RapidBlink,
+        "reverse" => Attribute::Reverse,
+        "hidden" => Attribute::Hidden,
+        "strike" | "strikethrough" | "s" => Attribute::Strikethrough,
+        "double_underline" => Attribute::DoubleUnderline,
+        "overline" => Attribute::Overline,
+        "curly_underline" => Attribute::CurlyUnderline,
+        "dotted_underline" => Attribute::DottedUnderline,
+        "dashed_underline" => Attribute::DashedUnderline,
+        _ => return None,
+    };
+
+    Some(attribute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Rgb;
+
+    #[test]
+    fn parenthesized_arguments_stay_together() {
+        let text = markup("[bold rgb(1, 2, 3) on rgb( 4,5, 6 )]x").unwrap();
+
+        assert_eq!(
+            text.spans[0].style,
+            Style::new()
+                .with_attribute(Attribute::Bold)
+                .with_fg(Rgb::new(1, 2, 3))
+                .with_bg(Rgb::new(4, 5, 6))
+        );
+
+        assert_eq!(
+            "rgb(1, 2, 3) bold".parse::<Style>(),
+            Ok(Style::new()
+                .with_fg(Rgb::new(1, 2, 3))
+                .with_attribute(Attribute::Bold))
+        );
+    }
+
+    #[test]
+    fn palette_indices_need_color() {
+        assert_eq!(
+            markup("a[0]b"),
+            Err(MarkupError {
+                position: 2,
+                kind: MarkupErrorKind::UnknownStyle("0".to_string()),
+            })
+        );
+
+        assert_eq!(
+            markup("[color(0) on color(202)]b").unwrap().spans[0].style,
+            Style::new()
+                .with_fg(Color::Extended(0))
+                .with_bg(Color::Extended(202))
+        );
+
+        assert_eq!(markup("a\\[0]b").unwrap().plain(), "a[0]b");
+    }
+
+    #[test]
+    fn escaped_brackets_inside_tags() {
+        assert_eq!(
+            markup("[bold\\]").unwrap_err().kind,
+            MarkupErrorKind::UnterminatedTag
+        );
+
+        assert_eq!(
+            markup("[bold \\]]x"),
+            Err(MarkupError {
+                position: 6,
+                kind: MarkupErrorKind::UnknownStyle("\\]".to_string()),
+            })
+        );
+
+        assert_eq!(markup("\\[bold\\]").unwrap().plain(), "[bold]");
+    }
+}
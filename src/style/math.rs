@@ -0,0 +1,341 @@
+use super::Rgb;
+
+/// A color in the HSL color space. Hue is in degrees `0.0..360.0`, saturation and lightness are in
+/// `0.0..=1.0`
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+/// A color in the OKLab perceptual color space. Equal distances in OKLab appear as roughly equal
+/// differences in color, which makes it suitable for blending and gradients
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// Whether a color scheme uses light text on a dark background or the opposite
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    Dark,
+    Light,
+}
+
+fn to_linear(channel: u8) -> f32 {
+    let c = channel as f32 / 255.0;
+
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(c: f32) -> u8 {
+    let c = if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Rgb {
+    pub fn to_hsl(&self) -> Hsl {
+        let r = self.r as f32 / 255.0;
+        let g = self.g as f32 / 255.0;
+        let b = self.b as f32 / 255.0;
+
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let delta = max - min;
+
+        let l = (max + min) / 2.0;
+
+        if delta == 0.0 {
+            return Hsl { h: 0.0, s: 0.0, l };
+        }
+
+        let s = delta / (1.0 - (2.0 * l - 1.0).abs());
+
+        let h = if max == r {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if max == g {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        };
+
+        Hsl { h, s, l }
+    }
+
+    pub fn from_hsl(hsl: Hsl) -> Self {
+        let h = hsl.h.rem_euclid(360.0);
+        let s = hsl.s.clamp(0.0, 1.0);
+        let l = hsl.l.clamp(0.0, 1.0);
+
+        let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+        let x = c * (1.0 - ((h / 60.0).rem_euclid(2.0) - 1.0).abs());
+        let m = l - c / 2.0;
+
+        let (r, g, b) = match (h / 60.0) as u32 {
+            0 => (c, x, 0.0),
+            1 => (x, c, 0.0),
+            2 => (0.0, c, x),
+            3 => (0.0, x, c),
+            4 => (x, 0.0, c),
+            _ => (c, 0.0, x),
+        };
+
+        let channel = |v: f32| ((v + m).clamp(0.0, 1.0) * 255.0).round() as u8;
+
+        Rgb::new(channel(r), channel(g), channel(b))
+    }
+
+    // The matrices are copied verbatim from the reference implementation at
+    // https://bottosson.github.io/posts/oklab
+    #[allow(clippy::excessive_precision)]
+    pub fn to_oklab(&self) -> Oklab {
+        let r = to_linear(self.r);
+        let g = to_linear(self.g);
+        let b = to_linear(self.b);
+
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+
+        Oklab {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+
+    #[allow(clippy::excessive_precision)]
+    pub fn from_oklab(lab: Oklab) -> Self {
+        let l = (lab.l + 0.3963377774 * lab.a + 0.2158037573 * lab.b).powi(3);
+        let m = (lab.l - 0.1055613458 * lab.a - 0.0638541728 * lab.b).powi(3);
+        let s = (lab.l - 0.0894841775 * lab.a - 1.2914855480 * lab.b).powi(3);
+
+        Rgb::new(
+            from_linear(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            from_linear(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            from_linear(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+        )
+    }
+
+    /// Interpolates between two colors in OKLab, `t` of `0.0` returns `self` and `1.0` returns
+    /// `other`
+    pub fn lerp(&self, other: &Rgb, t: f32) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+
+        let a = self.to_oklab();
+        let b = other.to_oklab();
+
+        Rgb::from_oklab(Oklab {
+            l: a.l + (b.l - a.l) * t,
+            a: a.a + (b.a - a.a) * t,
+            b: a.b + (b.b - a.b) * t,
+        })
+    }
+
+    /// Returns `steps` colors evenly spread over a gradient through `stops`
+    pub fn gradient(stops: &[Rgb], steps: usize) -> Vec<Rgb> {
+        match (stops, steps) {
+            ([], _) | (_, 0) => Vec::new(),
+            ([only], _) => vec![*only; steps],
+            (_, 1) => vec![stops[0]],
+            _ => (0..steps)
+                .map(|i| {
+                    let position = i as f32 / (steps - 1) as f32 * (stops.len() - 1) as f32;
+                    let idx = (position as usize).min(stops.len() - 2);
+
+                    stops[idx].lerp(&stops[idx + 1], position - idx as f32)
+                })
+                .collect(),
+        }
+    }
+
+    /// Increases the HSL lightness by `amount`, in `0.0..=1.0`
+    pub fn lighten(&self, amount: f32) -> Rgb {
+        let mut hsl = self.to_hsl();
+        hsl.l = (hsl.l + amount).clamp(0.0, 1.0);
+        Rgb::from_hsl(hsl)
+    }
+
+    /// Decreases the HSL lightness by `amount`, in `0.0..=1.0`
+    pub fn darken(&self, amount: f32) -> Rgb {
+        self.lighten(-amount)
+    }
+
+    /// Increases the HSL saturation by `amount`, in `0.0..=1.0`
+    pub fn saturate(&self, amount: f32) -> Rgb {
+        let mut hsl = self.to_hsl();
+        hsl.s = (hsl.s + amount).clamp(0.0, 1.0);
+        Rgb::from_hsl(hsl)
+    }
+
+    /// Decreases the HSL saturation by `amount`, in `0.0..=1.0`
+    pub fn desaturate(&self, amount: f32) -> Rgb {
+        self.saturate(-amount)
+    }
+
+    /// Returns the relative luminance as defined by WCAG 2, in `0.0..=1.0`
+    pub fn relative_luminance(&self) -> f32 {
+        0.2126 * to_linear(self.r) + 0.7152 * to_linear(self.g) + 0.0722 * to_linear(self.b)
+    }
+
+    /// Returns the WCAG 2 contrast ratio between two colors, in `1.0..=21.0`. A ratio of at least
+    /// 4.5 is recommended for body text and 3.0 for large text
+    pub fn contrast_ratio(&self, other: &Rgb) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    /// Returns black or white, whichever has the higher contrast when used as text on top of this
+    /// color
+    pub fn readable_foreground(&self) -> Rgb {
+        let black = Rgb::new(0, 0, 0);
+        let white = Rgb::new(255, 255, 255);
+
+        if self.contrast_ratio(&black) >= self.contrast_ratio(&white) {
+            black
+        } else {
+            white
+        }
+    }
+}
+
+impl Rgb {
+    /// Classifies this color as the background of a dark or light color scheme. A background is
+    /// dark when white text has a higher contrast on it than black text
+    pub fn color_scheme(&self) -> ColorScheme {
+        if self.readable_foreground() == Rgb::new(255, 255, 255) {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+}
+
+impl From<Hsl> for Rgb {
+    fn from(value: Hsl) -> Self {
+        Rgb::from_hsl(value)
+    }
+}
+
+impl From<Rgb> for Hsl {
+    fn from(value: Rgb) -> Self {
+        value.to_hsl()
+    }
+}
+
+impl From<Oklab> for Rgb {
+    fn from(value: Oklab) -> Self {
+        Rgb::from_oklab(value)
+    }
+}
+
+impl From<Rgb> for Oklab {
+    fn from(value: Rgb) -> Self {
+        value.to_oklab()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLACK: Rgb = Rgb::new(0, 0, 0);
+    const WHITE: Rgb = Rgb::new(255, 255, 255);
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-3, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn contrast() {
+        assert_close(WHITE.contrast_ratio(&BLACK), 21.0);
+        assert_close(BLACK.contrast_ratio(&WHITE), 21.0);
+        assert_close(WHITE.contrast_ratio(&WHITE), 1.0);
+
+        assert_eq!(Rgb::new(255, 255, 0).readable_foreground(), BLACK);
+        assert_eq!(Rgb::new(0, 0, 128).readable_foreground(), WHITE);
+        assert_eq!(Rgb::new(30, 30, 30).color_scheme(), ColorScheme::Dark);
+        assert_eq!(Rgb::new(250, 250, 240).color_scheme(), ColorScheme::Light);
+    }
+
+    #[test]
+    fn oklab_round_trip() {
+        for r in (0..=255).step_by(15) {
+            for g in (0..=255).step_by(15) {
+                for b in (0..=255).step_by(15) {
+                    let rgb = Rgb::new(r as u8, g as u8, b as u8);
+
+                    assert_eq!(Rgb::from_oklab(rgb.to_oklab()), rgb);
+                }
+            }
+        }
+
+        let white = WHITE.to_oklab();
+        assert_close(white.l, 1.0);
+        assert_close(white.a, 0.0);
+        assert_close(white.b, 0.0);
+    }
+
+    #[test]
+    fn hsl() {
+        let primaries = [
+            (Rgb::new(255, 0, 0), 0.0),
+            (Rgb::new(0, 255, 0), 120.0),
+            (Rgb::new(0, 0, 255), 240.0),
+        ];
+
+        for (rgb, hue) in primaries {
+            let hsl = rgb.to_hsl();
+
+            assert_close(hsl.h, hue);
+            assert_close(hsl.s, 1.0);
+            assert_close(hsl.l, 0.5);
+            assert_eq!(Rgb::from_hsl(hsl), rgb);
+        }
+
+        assert_eq!(
+            BLACK.to_hsl(),
+            Hsl {
+                h: 0.0,
+                s: 0.0,
+                l: 0.0
+            }
+        );
+        assert_eq!(Rgb::new(128, 0, 0).lighten(0.25), Rgb::new(255, 0, 0));
+        assert_eq!(Rgb::new(255, 0, 0).desaturate(1.0), Rgb::new(128, 128, 128));
+    }
+
+    #[test]
+    fn blending() {
+        let red = Rgb::new(255, 0, 0);
+        let blue = Rgb::new(0, 0, 255);
+
+        assert_eq!(red.lerp(&blue, 0.0), red);
+        assert_eq!(red.lerp(&blue, 1.0), blue);
+        assert_eq!(red.lerp(&blue, 2.0), blue);
+        assert_eq!(BLACK.lerp(&WHITE, 0.5), Rgb::new(99, 99, 99));
+
+        assert_eq!(Rgb::gradient(&[red, blue], 0), []);
+        assert_eq!(Rgb::gradient(&[red], 3), [red; 3]);
+
+        let gradient = Rgb::gradient(&[red, BLACK, blue], 5);
+        assert_eq!(gradient.len(), 5);
+        assert_eq!(gradient[0], red);
+        assert_eq!(gradient[2], BLACK);
+        assert_eq!(gradient[4], blue);
+    }
+}
@@ -0,0 +1,375 @@
+/// Marker struct used to identify types of color codes
+pub struct ForegroundCode;
+
+/// Marker struct used to identify types of color codes
+pub struct BackgroundCode;
+
+/// Marker struct used to identify types of color codes
+pub struct UnderlineCode;
+
+/// A color which can be encoded as SGR parameters for the target marked by `T`
+pub trait ColorCode<T> {
+    /// Returns the SGR parameters selecting this color, without the leading `\x1b[` and the
+    /// trailing `m`
+    fn code(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+
+    /// An index into the 256 color palette
+    Extended(u8),
+
+    /// A 24-bit truecolor value
+    Rgb(Rgb),
+
+    Default,
+    Reset,
+}
+
+impl Color {
+    /// The 16 named colors in palette order
+    pub const NAMED: [Color; 16] = [
+        Color::Black,
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::White,
+        Color::BrightBlack,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+        Color::BrightWhite,
+    ];
+
+    /// Returns the palette index of a named color, `0..=15`. Other colors return `None`
+    pub fn ansi_index(&self) -> Option<u8> {
+        Color::NAMED.iter().position(|i| i == self).map(|i| i as u8)
+    }
+}
+
+impl ColorCode<ForegroundCode> for Color {
+    fn code(&self) -> String {
+        match self {
+            Color::Black => "30".into(),
+            Color::Red => "31".into(),
+            Color::Green => "32".into(),
+            Color::Yellow => "33".into(),
+            Color::Blue => "34".into(),
+            Color::Magenta => "35".into(),
+            Color::Cyan => "36".into(),
+            Color::White => "37".into(),
+            Color::BrightBlack => "90".into(),
+            Color::BrightRed => "91".into(),
+            Color::BrightGreen => "92".into(),
+            Color::BrightYellow => "93".into(),
+            Color::BrightBlue => "94".into(),
+            Color::BrightMagenta => "95".into(),
+            Color::BrightCyan => "96".into(),
+            Color::BrightWhite => "97".into(),
+            Color::Extended(index) => format!("38;5;{}", index),
+            Color::Rgb(Rgb { r, g, b }) => format!("38;2;{};{};{}", r, g, b),
+            Color::Default => "39".into(),
+            Color::Reset => "0".into(),
+        }
+    }
+}
+
+impl ColorCode<BackgroundCode> for Color {
+    fn code(&self) -> String {
+        match self {
+            Color::Black => "40".into(),
+            Color::Red => "41".into(),
+            Color::Green => "42".into(),
+            Color::Yellow => "43".into(),
+            Color::Blue => "44".into(),
+            Color::Magenta => "45".into(),
+            Color::Cyan => "46".into(),
+            Color::White => "47".into(),
+            Color::BrightBlack => "100".into(),
+            Color::BrightRed => "101".into(),
+            Color::BrightGreen => "102".into(),
+            Color::BrightYellow => "103".into(),
+            Color::BrightBlue => "104".into(),
+            Color::BrightMagenta => "105".into(),
+            Color::BrightCyan => "106".into(),
+            Color::BrightWhite => "107".into(),
+            Color::Extended(index) => format!("48;5;{}", index),
+            Color::Rgb(Rgb { r, g, b }) => format!("48;2;{};{};{}", r, g, b),
+            Color::Default => "49".into(),
+            Color::Reset => "0".into(),
+        }
+    }
+}
+
+/// Underline colors use the colon separated sub-parameter form, which terminals without underline
+/// color support are more likely to ignore than the semicolon form
+impl ColorCode<UnderlineCode> for Color {
+    fn code(&self) -> String {
+        if let Some(index) = self.ansi_index() {
+            return format!("58:5:{}", index);
+        }
+
+        match self {
+            Color::Extended(index) => format!("58:5:{}", index),
+            Color::Rgb(Rgb { r, g, b }) => format!("58:2::{}:{}:{}", r, g, b),
+            Color::Default => "59".into(),
+            _ => "0".into(),
+        }
+    }
+}
+
+/// An index into the 256 color palette
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExtendedColor(pub u8);
+
+impl From<ExtendedColor> for Color {
+    fn from(value: ExtendedColor) -> Self {
+        Color::Extended(value.0)
+    }
+}
+
+impl<T> ColorCode<T> for ExtendedColor
+where
+    Color: ColorCode<T>,
+{
+    fn code(&self) -> String {
+        Color::from(*self).code()
+    }
+}
+
+/// A 24-bit truecolor value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+}
+
+impl From<Rgb> for Color {
+    fn from(value: Rgb) -> Self {
+        Color::Rgb(value)
+    }
+}
+
+impl<T> ColorCode<T> for Rgb
+where
+    Color: ColorCode<T>,
+{
+    fn code(&self) -> String {
+        Color::from(*self).code()
+    }
+}
+
+/// A text attribute set using SGR parameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attribute {
+    Bold,
+    Dim,
+    Italic,
+    Underline,
+    SlowBlink,
+    RapidBlink,
+    Reverse,
+    Hidden,
+    Strikethrough,
+    DoubleUnderline,
+    Overline,
+
+    /// Extended underline style, only supported by some terminals
+    CurlyUnderline,
+
+    /// Extended underline style, only supported by some terminals
+    DottedUnderline,
+
+    /// Extended underline style, only supported by some terminals
+    DashedUnderline,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 14] = [
+        Attribute::Bold,
+        Attribute::Dim,
+        Attribute::Italic,
+        Attribute::Underline,
+        Attribute::SlowBlink,
+        Attribute::RapidBlink,
+        Attribute::Reverse,
+        Attribute::Hidden,
+        Attribute::Strikethrough,
+        Attribute::DoubleUnderline,
+        Attribute::Overline,
+        Attribute::CurlyUnderline,
+        Attribute::DottedUnderline,
+        Attribute::DashedUnderline,
+    ];
+
+    /// Returns the SGR parameter enabling this attribute
+    pub fn code(&self) -> &'static str {
+        use Attribute::*;
+
+        match self {
+            Bold => "1",
+            Dim => "2",
+            Italic => "3",
+            Underline => "4",
+            SlowBlink => "5",
+            RapidBlink => "6",
+            Reverse => "7",
+            Hidden => "8",
+            Strikethrough => "9",
+            DoubleUnderline => "21",
+            Overline => "53",
+            CurlyUnderline => "4:3",
+            DottedUnderline => "4:4",
+            DashedUnderline => "4:5",
+        }
+    }
+
+    /// Returns the SGR parameter disabling this attribute without affecting any other state. Note
+    /// that some attributes share a reset code, for example `Bold` and `Dim` are both reset by
+    /// `22`, and every underline style is reset by `24`.
+    pub fn reset_code(&self) -> &'static str {
+        use Attribute::*;
+
+        match self {
+            Bold | Dim => "22",
+            Italic => "23",
+            Underline | DoubleUnderline | CurlyUnderline | DottedUnderline | DashedUnderline => {
+                "24"
+            }
+            SlowBlink | RapidBlink => "25",
+            Reverse => "27",
+            Hidden => "28",
+            Strikethrough => "29",
+            Overline => "55",
+        }
+    }
+
+    fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+/// A set of text attributes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Attributes(u16);
+
+impl Attributes {
+    pub fn empty() -> Self {
+        Self(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(&self, attribute: Attribute) -> bool {
+        self.0 & attribute.bit() != 0
+    }
+
+    pub fn insert(&mut self, attribute: Attribute) {
+        self.0 |= attribute.bit();
+    }
+
+    pub fn remove(&mut self, attribute: Attribute) {
+        self.0 &= !attribute.bit();
+    }
+
+    /// Returns a copy of this set with `attribute` added
+    pub fn with(mut self, attribute: Attribute) -> Self {
+        self.insert(attribute);
+        self
+    }
+
+    /// Returns a copy of this set with `attribute` removed
+    pub fn without(mut self, attribute: Attribute) -> Self {
+        self.remove(attribute);
+        self
+    }
+
+    /// Returns an iterator over the attributes in this set
+    pub fn iter(&self) -> impl Iterator<Item = Attribute> {
+        let set = *self;
+
+        Attribute::ALL.into_iter().filter(move |i| set.contains(*i))
+    }
+}
+
+impl From<Attribute> for Attributes {
+    fn from(value: Attribute) -> Self {
+        Self::empty().with(value)
+    }
+}
+
+impl std::ops::BitOr<Attribute> for Attributes {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attribute) -> Self::Output {
+        self.with(rhs)
+    }
+}
+
+impl std::ops::BitOr for Attribute {
+    type Output = Attributes;
+
+    fn bitor(self, rhs: Attribute) -> Self::Output {
+        Attributes::from(self).with(rhs)
+    }
+}
+
+impl FromIterator<Attribute> for Attributes {
+    fn from_iter<T: IntoIterator<Item = Attribute>>(iter: T) -> Self {
+        iter.into_iter().fold(Self::empty(), Self::with)
+    }
+}
+
+mod ansi;
+mod downsample;
+mod markup;
+mod math;
+mod parse;
+mod sgr;
+mod stylize;
+mod theme;
+mod writer;
+mod x11;
+
+pub(crate) use ansi::apply_sgr;
+pub use ansi::{parse_ansi, strip_ansi, visible_width};
+pub use downsample::ColorLevel;
+pub use markup::{markup, MarkupError, MarkupErrorKind, Span, Text};
+pub use math::{ColorScheme, Hsl, Oklab};
+pub use parse::ParseColorError;
+pub use sgr::Style;
+pub use stylize::{colors_enabled, set_colors_enabled, Styled, Stylize};
+pub use theme::{Theme, ThemeError, ThemeErrorKind};
+pub use writer::StyleWriter;
@@ -0,0 +1,237 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::{x11::X11_COLORS, Color, Rgb};
+
+/// An error returned when a string does not describe a color
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseColorError {
+    input: String,
+}
+
+impl Display for ParseColorError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid color \"{}\"", self.input)
+    }
+}
+
+impl std::error::Error for ParseColorError {}
+
+/// Lowercases a color name and removes separators so `"Slate Gray"`, `"slate-gray"` and
+/// `"SlateGray"` are treated the same
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Parses `#rgb`, `#rrggbb`, `rgb(r, g, b)` and X11 color names
+fn parse_rgb(s: &str) -> Option<Rgb> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if !hex.is_ascii() {
+            return None;
+        }
+
+        return match hex.len() {
+            3 => {
+                let channel =
+                    |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+
+                Some(Rgb::new(channel(0)?, channel(1)?, channel(2)?))
+            }
+            6 => {
+                let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+                Some(Rgb::new(channel(0)?, channel(2)?, channel(4)?))
+            }
+            _ => None,
+        };
+    }
+
+    if let Some(channels) = s.strip_prefix("rgb(").and_then(|i| i.strip_suffix(')')) {
+        let mut channels = channels.split(',').map(|i| i.trim().parse::<u8>().ok());
+
+        let rgb = Rgb::new(channels.next()??, channels.next()??, channels.next()??);
+
+        return channels.next().is_none().then_some(rgb);
+    }
+
+    let name = normalize(s);
+
+    X11_COLORS
+        .binary_search_by(|(key, _)| (*key).cmp(name.as_str()))
+        .ok()
+        .map(|i| X11_COLORS[i].1)
+}
+
+/// Parses a truecolor value from `#rgb`, `#rrggbb`, `rgb(r, g, b)` or an X11 color name such as
+/// `SlateGray`
+impl FromStr for Rgb {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_rgb(s.trim()).ok_or_else(|| ParseColorError {
+            input: s.to_string(),
+        })
+    }
+}
+
+/// Writes the color as `#rrggbb`
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// Parses a color from a named color (`red`, `bright-blue`), a palette index (`202` or
+/// `color(202)`), `default`, `reset` or any format accepted by `Rgb`. Named colors take precedence
+/// over X11 names, so `"red"` is `Color::Red` rather than the X11 value
+impl FromStr for Color {
+    type Err = ParseColorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Ok(index) = s.parse::<u8>() {
+            return Ok(Color::Extended(index));
+        }
+
+        if let Some(index) = s.strip_prefix("color(").and_then(|i| i.strip_suffix(')')) {
+            if let Ok(index) = index.trim().parse::<u8>() {
+                return Ok(Color::Extended(index));
+            }
+        }
+
+        let name = normalize(s);
+
+        let named = Color::NAMED
+            .iter()
+            .chain(&[Color::Default, Color::Reset])
+            .find(|color| normalize(&color.to_string()) == name);
+
+        if let Some(color) = named {
+            return Ok(*color);
+        }
+
+        s.parse::<Rgb>().map(Color::Rgb)
+    }
+}
+
+/// Writes the canonical name of the color, which parses back into the same color
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Color::Black => "black",
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::White => "white",
+            Color::BrightBlack => "bright-black",
+            Color::BrightRed => "bright-red",
+            Color::BrightGreen => "bright-green",
+            Color::BrightYellow => "bright-yellow",
+            Color::BrightBlue => "bright-blue",
+            Color::BrightMagenta => "bright-magenta",
+            Color::BrightCyan => "bright-cyan",
+            Color::BrightWhite => "bright-white",
+            Color::Extended(index) => return write!(f, "{}", index),
+            Color::Rgb(rgb) => return rgb.fmt(f),
+            Color::Default => "default",
+            Color::Reset => "reset",
+        };
+
+        f.write_str(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        assert_eq!("#ff8000".parse(), Ok(Rgb::new(255, 128, 0)));
+        assert_eq!("#FF8000".parse(), Ok(Rgb::new(255, 128, 0)));
+        assert_eq!("#f80".parse(), Ok(Rgb::new(255, 136, 0)));
+        assert_eq!(" #000 ".parse(), Ok(Rgb::new(0, 0, 0)));
+    }
+
+    #[test]
+    fn rgb_function() {
+        assert_eq!("rgb(1,2,3)".parse(), Ok(Rgb::new(1, 2, 3)));
+        assert_eq!("rgb( 255 , 0, 128 )".parse(), Ok(Rgb::new(255, 0, 128)));
+    }
+
+    #[test]
+    fn palette_indices() {
+        assert_eq!("0".parse(), Ok(Color::Extended(0)));
+        assert_eq!("202".parse(), Ok(Color::Extended(202)));
+        assert_eq!("color(202)".parse(), Ok(Color::Extended(202)));
+        assert_eq!("color( 7 )".parse(), Ok(Color::Extended(7)));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("red".parse(), Ok(Color::Red));
+        assert_eq!("Bright Blue".parse(), Ok(Color::BrightBlue));
+        assert_eq!("bright_white".parse(), Ok(Color::BrightWhite));
+        assert_eq!("default".parse(), Ok(Color::Default));
+        assert_eq!("reset".parse(), Ok(Color::Reset));
+
+        // X11 names ignore case and separators
+        let slate_gray = Color::Rgb(Rgb::new(112, 128, 144));
+
+        assert_eq!("slategray".parse(), Ok(slate_gray));
+        assert_eq!("SlateGray".parse(), Ok(slate_gray));
+        assert_eq!("slate-gray".parse(), Ok(slate_gray));
+        assert_eq!("Slate Gray".parse(), Ok(slate_gray));
+    }
+
+    #[test]
+    fn rejected() {
+        for input in [
+            "",
+            "#",
+            "#12",
+            "#1234",
+            "#12345g",
+            "#ééé",
+            "rgb(1,2)",
+            "rgb(1,2,3,4)",
+            "rgb(256,0,0)",
+            "rgb(1,2,3",
+            "256",
+            "-1",
+            "color(256)",
+            "color()",
+            "notacolor",
+        ] {
+            assert!(input.parse::<Color>().is_err(), "{:?} was accepted", input);
+        }
+
+        assert_eq!(
+            "nope".parse::<Rgb>().unwrap_err().to_string(),
+            "invalid color \"nope\""
+        );
+    }
+
+    #[test]
+    fn display_round_trip() {
+        let colors = Color::NAMED.iter().copied().chain([
+            Color::Extended(0),
+            Color::Extended(255),
+            Color::Rgb(Rgb::new(1, 128, 255)),
+            Color::Default,
+            Color::Reset,
+        ]);
+
+        for color in colors {
+            assert_eq!(color.to_string().parse(), Ok(color));
+        }
+
+        assert_eq!(Rgb::new(1, 128, 255).to_string(), "#0180ff");
+    }
+}
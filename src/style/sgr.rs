@@ -0,0 +1,322 @@
+use crate::capabilities::Capabilities;
+
+use super::{
+    Attribute, Attributes, BackgroundCode, Color, ColorCode, ForegroundCode, UnderlineCode,
+};
+
+/// A complete pen state: colors and attributes applied to text. Colors set to `None` use the
+/// terminal default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub underline_color: Option<Color>,
+    pub attributes: Attributes,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_fg(mut self, color: impl Into<Color>) -> Self {
+        self.fg = Some(color.into());
+        self
+    }
+
+    pub fn with_bg(mut self, color: impl Into<Color>) -> Self {
+        self.bg = Some(color.into());
+        self
+    }
+
+    pub fn with_underline_color(mut self, color: impl Into<Color>) -> Self {
+        self.underline_color = Some(color.into());
+        self
+    }
+
+    pub fn with_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.insert(attribute);
+        self
+    }
+
+    pub fn without_attribute(mut self, attribute: Attribute) -> Self {
+        self.attributes.remove(attribute);
+        self
+    }
+
+    /// Returns whether this style leaves text unstyled
+    pub fn is_plain(&self) -> bool {
+        self.normalized() == Style::default()
+    }
+
+    /// Returns a copy of this style with `other` layered on top. Colors set in `other` replace
+    /// the colors of this style and attributes are combined
+    pub fn patch(mut self, other: Style) -> Self {
+        self.fg = other.fg.or(self.fg);
+        self.bg = other.bg.or(self.bg);
+        self.underline_color = other.underline_color.or(self.underline_color);
+
+        for attribute in other.attributes.iter() {
+            self.attributes.insert(attribute);
+        }
+
+        self
+    }
+
+    /// Returns a copy of this style which can be displayed with `capabilities`. Colors are
+    /// downsampled and underline colors are removed if unsupported
+    pub fn adapt(mut self, capabilities: &Capabilities) -> Self {
+        let level = capabilities.color_level;
+
+        self.fg = self.fg.and_then(|color| color.downsample(level));
+        self.bg = self.bg.and_then(|color| color.downsample(level));
+        self.underline_color = self
+            .underline_color
+            .and_then(|color| color.downsample(level));
+
+        if !capabilities.underline_color {
+            self.underline_color = None;
+        }
+
+        self
+    }
+
+    /// Returns the escape sequence applying this style from a fully reset state, or an empty
+    /// string for a plain style
+    pub fn sequence(&self) -> String {
+        let params = self.normalized().params();
+
+        if params.is_empty() {
+            return String::new();
+        }
+
+        format!("\x1b[{}m", params.join(";"))
+    }
+
+    /// Returns the shortest escape sequence changing the pen from this style to `next`. Only the
+    /// parameters that differ are written, combined into a single sequence. A full reset followed
+    /// by the parameters of `next` is used instead when that is shorter. An empty string is
+    /// returned when both styles are the same
+    pub fn diff(&self, next: &Style) -> String {
+        let current = self.normalized();
+        let next = next.normalized();
+
+        if current == next {
+            return String::new();
+        }
+
+        let incremental = current.incremental_params(&next).join(";");
+
+        let mut full = vec!["0".to_string()];
+        full.extend(next.params());
+        let full = full.join(";");
+
+        if full.len() < incremental.len() {
+            format!("\x1b[{}m", full)
+        } else {
+            format!("\x1b[{}m", incremental)
+        }
+    }
+
+    /// Returns the escape sequence resetting exactly what this style set, leaving any other state
+    /// untouched. Used to end a styled span without a full reset
+    pub fn reset_sequence(&self) -> String {
+        let style = self.normalized();
+        let mut params: Vec<String> = Vec::new();
+
+        for attribute in style.attributes.iter() {
+            let code = attribute.reset_code().to_string();
+
+            if !params.contains(&code) {
+                params.push(code);
+            }
+        }
+
+        if style.fg.is_some() {
+            params.push("39".into());
+        }
+
+        if style.bg.is_some() {
+            params.push("49".into());
+        }
+
+        if style.underline_color.is_some() {
+            params.push("59".into());
+        }
+
+        if params.is_empty() {
+            return String::new();
+        }
+
+        format!("\x1b[{}m", params.join(";"))
+    }
+
+    /// `Color::Reset` and `Color::Default` are both stored as `None` so equal pens compare equal
+    fn normalized(mut self) -> Self {
+        let normalize = |color: Option<Color>| match color {
+            Some(Color::Reset) | Some(Color::Default) => None,
+            color => color,
+        };
+
+        self.fg = normalize(self.fg);
+        self.bg = normalize(self.bg);
+        self.underline_color = normalize(self.underline_color);
+
+        self
+    }
+
+    fn params(&self) -> Vec<String> {
+        let mut params: Vec<String> = self.attributes.iter().map(|i| i.code().into()).collect();
+
+        if let Some(fg) = self.fg {
+            params.push(ColorCode::<ForegroundCode>::code(&fg));
+        }
+
+        if let Some(bg) = self.bg {
+            params.push(ColorCode::<BackgroundCode>::code(&bg));
+        }
+
+        if let Some(underline_color) = self.underline_color {
+            params.push(ColorCode::<UnderlineCode>::code(&underline_color));
+        }
+
+        params
+    }
+
+    fn incremental_params(&self, next: &Style) -> Vec<String> {
+        let mut params: Vec<String> = Vec::new();
+
+        // Attributes sharing a reset code, such as bold and dim, have to be enabled again when
+        // only one of them is removed
+        let mut resets: Vec<&str> = Vec::new();
+
+        for attribute in self.attributes.iter() {
+            if !next.attributes.contains(attribute) && !resets.contains(&attribute.reset_code()) {
+                resets.push(attribute.reset_code());
+            }
+        }
+
+        params.extend(resets.iter().map(|i| i.to_string()));
+
+        for attribute in next.attributes.iter() {
+            if !self.attributes.contains(attribute) || resets.contains(&attribute.reset_code()) {
+                params.push(attribute.code().into());
+            }
+        }
+
+        if self.fg != next.fg {
+            params.push(match next.fg {
+                Some(fg) => ColorCode::<ForegroundCode>::code(&fg),
+                None => "39".into(),
+            });
+        }
+
+        if self.bg != next.bg {
+            params.push(match next.bg {
+                Some(bg) => ColorCode::<BackgroundCode>::code(&bg),
+                None => "49".into(),
+            });
+        }
+
+        if self.underline_color != next.underline_color {
+            params.push(match next.underline_color {
+                Some(underline_color) => ColorCode::<UnderlineCode>::code(&underline_color),
+                None => "59".into(),
+            });
+        }
+
+        params
+    }
+}
+
+impl From<Attributes> for Style {
+    fn from(attributes: Attributes) -> Self {
+        Self {
+            attributes,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::style::Rgb;
+
+    #[test]
+    fn same_styles_produce_nothing() {
+        let style = Style::new()
+            .with_fg(Color::Red)
+            .with_attribute(Attribute::Bold);
+
+        assert_eq!(style.diff(&style), "");
+        assert_eq!(Style::new().diff(&Style::new().with_fg(Color::Reset)), "");
+    }
+
+    #[test]
+    fn removing_bold_enables_dim_again() {
+        let current = Style::new()
+            .with_attribute(Attribute::Bold)
+            .with_attribute(Attribute::Dim);
+        let next = Style::new().with_attribute(Attribute::Dim);
+
+        // 22 clears both bold and dim
+        assert_eq!(current.incremental_params(&next), ["22", "2"]);
+        assert_eq!(next.reset_sequence(), "\x1b[22m");
+        assert_eq!(current.reset_sequence(), "\x1b[22m");
+
+        let current = current.with_fg(Color::Rgb(Rgb::new(10, 20, 30)));
+        let next = next.with_fg(Color::Rgb(Rgb::new(10, 20, 30)));
+        assert_eq!(current.diff(&next), "\x1b[22;2m");
+    }
+
+    #[test]
+    fn underline_reset_clears_every_underline_style() {
+        let current = Style::new()
+            .with_fg(Color::Extended(100))
+            .with_attribute(Attribute::Italic)
+            .with_attribute(Attribute::CurlyUnderline)
+            .with_attribute(Attribute::DoubleUnderline);
+
+        assert_eq!(
+            current.diff(&current.without_attribute(Attribute::DoubleUnderline)),
+            "\x1b[24;4:3m"
+        );
+        assert_eq!(
+            current.diff(
+                &current
+                    .without_attribute(Attribute::CurlyUnderline)
+                    .without_attribute(Attribute::DoubleUnderline)
+            ),
+            "\x1b[24m"
+        );
+        assert_eq!(
+            current
+                .without_attribute(Attribute::Italic)
+                .without_attribute(Attribute::Bold)
+                .reset_sequence(),
+            "\x1b[24;39m"
+        );
+    }
+
+    #[test]
+    fn full_reset_when_shorter() {
+        let current = Style::new()
+            .with_attribute(Attribute::Bold)
+            .with_attribute(Attribute::Italic)
+            .with_attribute(Attribute::Underline)
+            .with_attribute(Attribute::Reverse);
+
+        assert_eq!(current.diff(&Style::new()), "\x1b[0m");
+        assert_eq!(
+            current.diff(&Style::new().with_fg(Color::Red)),
+            "\x1b[0;31m"
+        );
+
+        // Turning on a single attribute is shorter than resetting everything
+        assert_eq!(
+            current.diff(&current.with_attribute(Attribute::Strikethrough)),
+            "\x1b[9m"
+        );
+    }
+}
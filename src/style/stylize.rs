@@ -0,0 +1,260 @@
+use std::{
+    fmt::Display,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+};
+
+use crate::{capabilities::Capabilities, style::ColorLevel};
+
+use super::{Attribute, Color, Style};
+
+const UNDETECTED: u8 = 0;
+const ENABLED: u8 = 1;
+const DISABLED: u8 = 2;
+
+static COLORS_ENABLED: AtomicU8 = AtomicU8::new(UNDETECTED);
+
+static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+
+fn detected_capabilities() -> Capabilities {
+    *CAPABILITIES.get_or_init(Capabilities::detect)
+}
+
+/// Returns the capabilities `Display` implementations adapt styles to, the same way
+/// `StyleWriter::detect` does. When styling was enabled with `set_colors_enabled` despite no color
+/// support being detected, 16 colors are assumed
+pub(crate) fn display_capabilities() -> Capabilities {
+    let mut capabilities = detected_capabilities();
+
+    if capabilities.color_level == ColorLevel::None {
+        capabilities.color_level = ColorLevel::Ansi16;
+    }
+
+    capabilities
+}
+
+/// Enables or disables styling for every `Styled` value formatted with `Display`. This overrides
+/// the default, which is detected from the environment on first use
+pub fn set_colors_enabled(enabled: bool) {
+    COLORS_ENABLED.store(if enabled { ENABLED } else { DISABLED }, Ordering::Relaxed);
+}
+
+/// Returns whether `Styled` values write their style when formatted. Unless overridden with
+/// `set_colors_enabled` this is disabled when `Capabilities::detect` reports `ColorLevel::None`,
+/// for example when `NO_COLOR` is set
+pub fn colors_enabled() -> bool {
+    match COLORS_ENABLED.load(Ordering::Relaxed) {
+        ENABLED => true,
+        DISABLED => false,
+        _ => {
+            let enabled = detected_capabilities().color_level != ColorLevel::None;
+
+            // A concurrent call to `set_colors_enabled` takes precedence over detection
+            let _ = COLORS_ENABLED.compare_exchange(
+                UNDETECTED,
+                if enabled { ENABLED } else { DISABLED },
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+
+            COLORS_ENABLED.load(Ordering::Relaxed) == ENABLED
+        }
+    }
+}
+
+/// A value displayed with a style. Formatting writes the style, the content and then resets
+/// exactly what the style changed, so surrounding text keeps its own style. Colors are downsampled
+/// to the detected color level like `StyleWriter` does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Styled<T> {
+    content: T,
+    style: Style,
+}
+
+impl<T> Styled<T> {
+    pub fn new(content: T, style: Style) -> Self {
+        Self { content, style }
+    }
+
+    pub fn content(&self) -> &T {
+        &self.content
+    }
+
+    pub fn style(&self) -> &Style {
+        &self.style
+    }
+
+    pub fn into_content(self) -> T {
+        self.content
+    }
+}
+
+impl<T> Display for Styled<T>
+where
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !colors_enabled() {
+            return self.content.fmt(f);
+        }
+
+        let style = self.style.adapt(&display_capabilities());
+
+        if style.is_plain() {
+            return self.content.fmt(f);
+        }
+
+        f.write_str(&style.sequence())?;
+        self.content.fmt(f)?;
+        f.write_str(&style.reset_sequence())
+    }
+}
+
+impl<T> AsMut<Style> for Styled<T> {
+    fn as_mut(&mut self) -> &mut Style {
+        &mut self.style
+    }
+}
+
+impl AsMut<Style> for Style {
+    fn as_mut(&mut self) -> &mut Style {
+        self
+    }
+}
+
+macro_rules! color_methods {
+    ($($fg:ident $bg:ident => $color:ident),* $(,)?) => {
+        $(
+            fn $fg(self) -> Self::Styled {
+                self.with(Color::$color)
+            }
+
+            fn $bg(self) -> Self::Styled {
+                self.on(Color::$color)
+            }
+        )*
+    };
+}
+
+macro_rules! attribute_methods {
+    ($($method:ident => $attribute:ident),* $(,)?) => {
+        $(
+            fn $method(self) -> Self::Styled {
+                self.attribute(Attribute::$attribute)
+            }
+        )*
+    };
+}
+
+/// Extension methods styling a value, for example `"Error".red().bold()`
+pub trait Stylize: Sized {
+    type Styled: AsMut<Style>;
+
+    fn stylize(self) -> Self::Styled;
+
+    /// Sets the foreground color
+    fn with(self, color: impl Into<Color>) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().fg = Some(color.into());
+        styled
+    }
+
+    /// Sets the background color
+    fn on(self, color: impl Into<Color>) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().bg = Some(color.into());
+        styled
+    }
+
+    /// Sets the underline color
+    fn underline_color(self, color: impl Into<Color>) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().underline_color = Some(color.into());
+        styled
+    }
+
+    fn attribute(self, attribute: Attribute) -> Self::Styled {
+        let mut styled = self.stylize();
+        styled.as_mut().attributes.insert(attribute);
+        styled
+    }
+
+    color_methods! {
+        black on_black => Black,
+        red on_red => Red,
+        green on_green => Green,
+        yellow on_yellow => Yellow,
+        blue on_blue => Blue,
+        magenta on_magenta => Magenta,
+        cyan on_cyan => Cyan,
+        white on_white => White,
+        bright_black on_bright_black => BrightBlack,
+        bright_red on_bright_red => BrightRed,
+        bright_green on_bright_green => BrightGreen,
+        bright_yellow on_bright_yellow => BrightYellow,
+        bright_blue on_bright_blue => BrightBlue,
+        bright_magenta on_bright_magenta => BrightMagenta,
+        bright_cyan on_bright_cyan => BrightCyan,
+        bright_white on_bright_white => BrightWhite,
+    }
+
+    attribute_methods! {
+        bold => Bold,
+        dim => Dim,
+        italic => Italic,
+        underlined => Underline,
+        slow_blink => SlowBlink,
+        rapid_blink => RapidBlink,
+        reverse => Reverse,
+        hidden => Hidden,
+        crossed_out => Strikethrough,
+        double_underlined => DoubleUnderline,
+        overlined => Overline,
+        curly_underlined => CurlyUnderline,
+        dotted_underlined => DottedUnderline,
+        dashed_underlined => DashedUnderline,
+    }
+}
+
+impl Stylize for Style {
+    type Styled = Style;
+
+    fn stylize(self) -> Self::Styled {
+        self
+    }
+}
+
+impl<T> Stylize for Styled<T> {
+    type Styled = Styled<T>;
+
+    fn stylize(self) -> Self::Styled {
+        self
+    }
+}
+
+macro_rules! impl_stylize {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl Stylize for $t {
+                type Styled = Styled<$t>;
+
+                fn stylize(self) -> Self::Styled {
+                    Styled::new(self, Style::default())
+                }
+            }
+        )*
+    };
+}
+
+impl_stylize!(String, char, bool, u8, u16, u32, u64, u128, usize);
+impl_stylize!(i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl<'a> Stylize for &'a str {
+    type Styled = Styled<&'a str>;
+
+    fn stylize(self) -> Self::Styled {
+        Styled::new(self, Style::default())
+    }
+}
@@ -0,0 +1,280 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use super::{Attribute, Color, MarkupError, Rgb, Style};
+
+/// A mapping of semantic roles such as `"error"` or `"selection"` to styles. Roles which are not
+/// set fall back to the base theme, if any.
+///
+/// Themes can be loaded from a simple text format with one `role = style` per line, using the
+/// same style syntax as markup tags. Lines starting with `#` are comments and `inherit = dark`
+/// uses a built-in theme as the base:
+///
+/// ```text
+/// inherit = dark
+/// error = bold #ff5555
+/// selection = on color(238)
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Theme {
+    roles: HashMap<String, Style>,
+    base: Option<Box<Theme>>,
+}
+
+impl Theme {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty theme which inherits every role from `base`
+    pub fn inherit(base: Theme) -> Self {
+        Self {
+            roles: HashMap::new(),
+            base: Some(Box::new(base)),
+        }
+    }
+
+    /// The built-in theme for dark backgrounds
+    pub fn dark() -> Self {
+        let bold = Style::new().with_attribute(Attribute::Bold);
+        let underline = Style::new().with_attribute(Attribute::Underline);
+
+        Self::from_roles([
+            ("error", bold.with_fg(Color::BrightRed)),
+            ("warning", Style::new().with_fg(Color::BrightYellow)),
+            ("info", Style::new().with_fg(Color::BrightCyan)),
+            ("success", Style::new().with_fg(Color::BrightGreen)),
+            ("title", bold),
+            ("muted", Style::new().with_fg(Rgb::new(0x7f, 0x84, 0x8e))),
+            ("border", Style::new().with_fg(Rgb::new(0x5c, 0x63, 0x70))),
+            (
+                "selection",
+                Style::new().with_bg(Rgb::new(0x26, 0x4f, 0x78)),
+            ),
+            (
+                "highlight",
+                Style::new()
+                    .with_fg(Color::Black)
+                    .with_bg(Color::BrightYellow),
+            ),
+            ("link", underline.with_fg(Color::BrightBlue)),
+        ])
+    }
+
+    /// The built-in theme for light backgrounds
+    pub fn light() -> Self {
+        let bold = Style::new().with_attribute(Attribute::Bold);
+        let underline = Style::new().with_attribute(Attribute::Underline);
+
+        Self::from_roles([
+            ("error", bold.with_fg(Color::Red)),
+            ("warning", Style::new().with_fg(Rgb::new(0xb3, 0x59, 0x00))),
+            ("info", Style::new().with_fg(Color::Blue)),
+            ("success", Style::new().with_fg(Color::Green)),
+            ("title", bold),
+            ("muted", Style::new().with_fg(Rgb::new(0x6a, 0x73, 0x7d))),
+            ("border", Style::new().with_fg(Rgb::new(0xc8, 0xcc, 0xd0))),
+            (
+                "selection",
+                Style::new().with_bg(Rgb::new(0xad, 0xd6, 0xff)),
+            ),
+            (
+                "highlight",
+                Style::new()
+                    .with_fg(Color::Black)
+                    .with_bg(Rgb::new(0xff, 0xf5, 0x9d)),
+            ),
+            ("link", underline.with_fg(Color::Blue)),
+        ])
+    }
+
+    fn from_roles<const N: usize>(roles: [(&str, Style); N]) -> Self {
+        Self {
+            roles: roles
+                .into_iter()
+                .map(|(role, style)| (role.to_string(), style))
+                .collect(),
+            base: None,
+        }
+    }
+
+    /// Returns a built-in theme by name, `"dark"` or `"light"`
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    pub fn base(&self) -> Option<&Theme> {
+        self.base.as_deref()
+    }
+
+    pub fn set(&mut self, role: impl Into<String>, style: Style) {
+        self.roles.insert(role.into(), style);
+    }
+
+    pub fn with(mut self, role: impl Into<String>, style: Style) -> Self {
+        self.set(role, style);
+        self
+    }
+
+    /// Returns the style of a role, searching the base themes if this theme does not set it
+    pub fn lookup(&self, role: &str) -> Option<Style> {
+        match self.roles.get(role) {
+            Some(style) => Some(*style),
+            None => self.base.as_ref()?.lookup(role),
+        }
+    }
+
+    /// Returns the style of a role, or a plain style if no theme in the chain sets it
+    pub fn get(&self, role: &str) -> Style {
+        self.lookup(role).unwrap_or_default()
+    }
+
+    /// Parses a theme, see `Theme` for the format
+    pub fn parse(text: &str) -> Result<Self, ThemeError> {
+        let mut theme = Theme::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |kind| ThemeError {
+                line: idx + 1,
+                kind,
+            };
+
+            let Some((role, style)) = line.split_once('=') else {
+                return Err(error(ThemeErrorKind::MissingEquals));
+            };
+
+            let role = role.trim();
+            let style = style.trim();
+
+            if role == "inherit" {
+                let base = Theme::builtin(style)
+                    .ok_or_else(|| error(ThemeErrorKind::UnknownBase(style.to_string())))?;
+
+                theme.base = Some(Box::new(base));
+
+                continue;
+            }
+
+            let style = style
+                .parse::<Style>()
+                .map_err(|e| error(ThemeErrorKind::InvalidStyle(e)))?;
+
+            theme.set(role, style);
+        }
+
+        Ok(theme)
+    }
+}
+
+impl FromStr for Theme {
+    type Err = ThemeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ThemeErrorKind {
+    /// A line which is neither empty, a comment nor `role = style`
+    MissingEquals,
+
+    /// `inherit` names a theme which is not built in
+    UnknownBase(String),
+
+    /// The style of a role could not be parsed
+    InvalidStyle(MarkupError),
+}
+
+/// An error in a theme file, with the 1-based line on which it occurred
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ThemeError {
+    pub line: usize,
+    pub kind: ThemeErrorKind,
+}
+
+impl Display for ThemeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ThemeErrorKind::MissingEquals => write!(f, "expected \"role = style\"")?,
+            ThemeErrorKind::UnknownBase(name) => write!(f, "unknown base theme \"{}\"", name)?,
+            ThemeErrorKind::InvalidStyle(e) => write!(f, "{}", e)?,
+        }
+
+        write!(f, " on line {}", self.line)
+    }
+}
+
+impl std::error::Error for ThemeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_themes_match_their_text_form() {
+        let dark = "
+            error = bold bright-red
+            warning = bright-yellow
+            info = bright-cyan
+            success = bright-green
+            title = bold
+            muted = #7f848e
+            border = #5c6370
+            selection = on #264f78
+            highlight = black on bright-yellow
+            link = underline bright-blue
+        ";
+
+        let light = "
+            error = bold red
+            warning = #b35900
+            info = blue
+            success = green
+            title = bold
+            muted = #6a737d
+            border = #c8ccd0
+            selection = on #add6ff
+            highlight = black on #fff59d
+            link = underline blue
+        ";
+
+        assert_eq!(dark.parse(), Ok(Theme::dark()));
+        assert_eq!(light.parse(), Ok(Theme::light()));
+    }
+
+    #[test]
+    fn roles() {
+        let dark = Theme::dark();
+
+        assert_eq!(
+            dark.get("error"),
+            Style::new()
+                .with_attribute(Attribute::Bold)
+                .with_fg(Color::BrightRed)
+        );
+        assert_eq!(
+            dark.get("selection"),
+            Style::new().with_bg(Rgb::new(0x26, 0x4f, 0x78))
+        );
+        assert_eq!(dark.lookup("unknown"), None);
+        assert_eq!(dark.get("unknown"), Style::default());
+
+        let theme: Theme = "inherit = light\nerror = italic".parse().unwrap();
+
+        assert_eq!(
+            theme.get("error"),
+            Style::new().with_attribute(Attribute::Italic)
+        );
+        assert_eq!(theme.get("info"), Theme::light().get("info"));
+    }
+}
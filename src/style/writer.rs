@@ -0,0 +1,163 @@
+use std::io::Write;
+
+use crate::capabilities::Capabilities;
+
+use super::{Color, ColorLevel, Style, Styled, Text};
+
+/// A writer which tracks the current pen and adapts styles to the capabilities of the terminal.
+/// Changing the style only writes the SGR parameters that differ from the current pen. Truecolor
+/// and 256 colors are downsampled to the closest supported color and colors are left out entirely
+/// under `ColorLevel::None`, for example when `NO_COLOR` is set. Text is passed through unchanged.
+///
+/// Escape sequences written directly through the `Write` implementation are not tracked, call
+/// `invalidate` after writing any that change the pen.
+pub struct StyleWriter<W> {
+    output: W,
+    capabilities: Capabilities,
+    current: Style,
+}
+
+impl<W> StyleWriter<W>
+where
+    W: Write,
+{
+    /// Creates a writer which assumes the pen of `output` is currently reset
+    pub fn new(output: W, capabilities: Capabilities) -> Self {
+        Self {
+            output,
+            capabilities,
+            current: Style::default(),
+        }
+    }
+
+    /// Creates a writer using capabilities detected from the environment, see
+    /// `Capabilities::detect`
+    pub fn detect(output: W) -> Self {
+        Self::new(output, Capabilities::detect())
+    }
+
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+
+    pub fn color_level(&self) -> ColorLevel {
+        self.capabilities.color_level
+    }
+
+    /// Returns the current pen, after adapting it to the capabilities of the terminal
+    pub fn style(&self) -> &Style {
+        &self.current
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.output
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.output
+    }
+
+    pub fn into_inner(self) -> W {
+        self.output
+    }
+
+    /// Changes the pen to `style`, writing only the difference from the current pen
+    pub fn set_style(&mut self, style: Style) -> std::io::Result<()> {
+        let style = style.adapt(&self.capabilities);
+
+        let sequence = self.current.diff(&style);
+
+        if !sequence.is_empty() {
+            self.output.write_all(sequence.as_bytes())?;
+        }
+
+        self.current = style;
+
+        Ok(())
+    }
+
+    /// Resets the pen with a full SGR reset, if it is not already reset
+    pub fn reset_style(&mut self) -> std::io::Result<()> {
+        if !self.current.is_plain() {
+            write!(self.output, "\x1b[0m")?;
+        }
+
+        self.current = Style::default();
+
+        Ok(())
+    }
+
+    /// Resets the pen unconditionally, bringing the tracked pen back in sync after escape sequences
+    /// were written directly
+    pub fn invalidate(&mut self) -> std::io::Result<()> {
+        write!(self.output, "\x1b[0m")?;
+
+        self.current = Style::default();
+
+        Ok(())
+    }
+
+    /// Writes a styled value using the capabilities of this writer instead of the global switch
+    /// used by its `Display` implementation. The pen is restored to its previous style afterwards
+    pub fn write_styled<T>(&mut self, styled: &Styled<T>) -> std::io::Result<()>
+    where
+        T: std::fmt::Display,
+    {
+        let previous = self.current;
+
+        self.set_style(previous.patch(*styled.style()))?;
+        write!(self.output, "{}", styled.content())?;
+        self.set_style(previous)
+    }
+
+    /// Writes styled text using the capabilities of this writer. Like `write_styled` the pen is
+    /// restored to its previous style afterwards
+    pub fn write_text(&mut self, text: &Text) -> std::io::Result<()> {
+        let previous = self.current;
+
+        for span in text.spans.iter() {
+            self.set_style(previous.patch(span.style))?;
+            self.output.write_all(span.content.as_bytes())?;
+        }
+
+        self.set_style(previous)
+    }
+
+    /// Sets the foreground color, downsampled to the color level of the terminal
+    pub fn set_fg(&mut self, color: impl Into<Color>) -> std::io::Result<()> {
+        self.set_style(Style {
+            fg: Some(color.into()),
+            ..self.current
+        })
+    }
+
+    /// Sets the background color, downsampled to the color level of the terminal
+    pub fn set_bg(&mut self, color: impl Into<Color>) -> std::io::Result<()> {
+        self.set_style(Style {
+            bg: Some(color.into()),
+            ..self.current
+        })
+    }
+
+    /// Sets the underline color, downsampled to the color level of the terminal. Nothing is
+    /// written if the terminal does not support underline colors
+    pub fn set_underline_color(&mut self, color: impl Into<Color>) -> std::io::Result<()> {
+        self.set_style(Style {
+            underline_color: Some(color.into()),
+            ..self.current
+        })
+    }
+}
+
+impl<W> Write for StyleWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.output.flush()
+    }
+}
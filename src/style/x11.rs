@@ -0,0 +1,685 @@
+use super::Rgb;
+
+/// Colors from the X11 `rgb.txt` table, keyed by lowercase names without spaces and sorted for
+/// binary search
+pub(crate) const X11_COLORS: [(&str, Rgb); 658] = [
+    ("aliceblue", Rgb::new(240, 248, 255)),
+    ("antiquewhite", Rgb::new(250, 235, 215)),
+    ("antiquewhite1", Rgb::new(255, 239, 219)),
+    ("antiquewhite2", Rgb::new(238, 223, 204)),
+    ("antiquewhite3", Rgb::new(205, 192, 176)),
+    ("antiquewhite4", Rgb::new(139, 131, 120)),
+    ("aquamarine", Rgb::new(127, 255, 212)),
+    ("aquamarine1", Rgb::new(127, 255, 212)),
+    ("aquamarine2", Rgb::new(118, 238, 198)),
+    ("aquamarine3", Rgb::new(102, 205, 170)),
+    ("aquamarine4", Rgb::new(69, 139, 116)),
+    ("azure", Rgb::new(240, 255, 255)),
+    ("azure1", Rgb::new(240, 255, 255)),
+    ("azure2", Rgb::new(224, 238, 238)),
+    ("azure3", Rgb::new(193, 205, 205)),
+    ("azure4", Rgb::new(131, 139, 139)),
+    ("beige", Rgb::new(245, 245, 220)),
+    ("bisque", Rgb::new(255, 228, 196)),
+    ("bisque1", Rgb::new(255, 228, 196)),
+    ("bisque2", Rgb::new(238, 213, 183)),
+    ("bisque3", Rgb::new(205, 183, 158)),
+    ("bisque4", Rgb::new(139, 125, 107)),
+    ("black", Rgb::new(0, 0, 0)),
+    ("blanchedalmond", Rgb::new(255, 235, 205)),
+    ("blue", Rgb::new(0, 0, 255)),
+    ("blue1", Rgb::new(0, 0, 255)),
+    ("blue2", Rgb::new(0, 0, 238)),
+    ("blue3", Rgb::new(0, 0, 205)),
+    ("blue4", Rgb::new(0, 0, 139)),
+    ("blueviolet", Rgb::new(138, 43, 226)),
+    ("brown", Rgb::new(165, 42, 42)),
+    ("brown1", Rgb::new(255, 64, 64)),
+    ("brown2", Rgb::new(238, 59, 59)),
+    ("brown3", Rgb::new(205, 51, 51)),
+    ("brown4", Rgb::new(139, 35, 35)),
+    ("burlywood", Rgb::new(222, 184, 135)),
+    ("burlywood1", Rgb::new(255, 211, 155)),
+    ("burlywood2", Rgb::new(238, 197, 145)),
+    ("burlywood3", Rgb::new(205, 170, 125)),
+    ("burlywood4", Rgb::new(139, 115, 85)),
+    ("cadetblue", Rgb::new(95, 158, 160)),
+    ("cadetblue1", Rgb::new(152, 245, 255)),
+    ("cadetblue2", Rgb::new(142, 229, 238)),
+    ("cadetblue3", Rgb::new(122, 197, 205)),
+    ("cadetblue4", Rgb::new(83, 134, 139)),
+    ("chartreuse", Rgb::new(127, 255, 0)),
+    ("chartreuse1", Rgb::new(127, 255, 0)),
+    ("chartreuse2", Rgb::new(118, 238, 0)),
+    ("chartreuse3", Rgb::new(102, 205, 0)),
+    ("chartreuse4", Rgb::new(69, 139, 0)),
+    ("chocolate", Rgb::new(210, 105, 30)),
+    ("chocolate1", Rgb::new(255, 127, 36)),
+    ("chocolate2", Rgb::new(238, 118, 33)),
+    ("chocolate3", Rgb::new(205, 102, 29)),
+    ("chocolate4", Rgb::new(139, 69, 19)),
+    ("coral", Rgb::new(255, 127, 80)),
+    ("coral1", Rgb::new(255, 114, 86)),
+    ("coral2", Rgb::new(238, 106, 80)),
+    ("coral3", Rgb::new(205, 91, 69)),
+    ("coral4", Rgb::new(139, 62, 47)),
+    ("cornflowerblue", Rgb::new(100, 149, 237)),
+    ("cornsilk", Rgb::new(255, 248, 220)),
+    ("cornsilk1", Rgb::new(255, 248, 220)),
+    ("cornsilk2", Rgb::new(238, 232, 205)),
+    ("cornsilk3", Rgb::new(205, 200, 177)),
+    ("cornsilk4", Rgb::new(139, 136, 120)),
+    ("cyan", Rgb::new(0, 255, 255)),
+    ("cyan1", Rgb::new(0, 255, 255)),
+    ("cyan2", Rgb::new(0, 238, 238)),
+    ("cyan3", Rgb::new(0, 205, 205)),
+    ("cyan4", Rgb::new(0, 139, 139)),
+    ("darkblue", Rgb::new(0, 0, 139)),
+    ("darkcyan", Rgb::new(0, 139, 139)),
+    ("darkgoldenrod", Rgb::new(184, 134, 11)),
+    ("darkgoldenrod1", Rgb::new(255, 185, 15)),
+    ("darkgoldenrod2", Rgb::new(238, 173, 14)),
+    ("darkgoldenrod3", Rgb::new(205, 149, 12)),
+    ("darkgoldenrod4", Rgb::new(139, 101, 8)),
+    ("darkgray", Rgb::new(169, 169, 169)),
+    ("darkgreen", Rgb::new(0, 100, 0)),
+    ("darkgrey", Rgb::new(169, 169, 169)),
+    ("darkkhaki", Rgb::new(189, 183, 107)),
+    ("darkmagenta", Rgb::new(139, 0, 139)),
+    ("darkolivegreen", Rgb::new(85, 107, 47)),
+    ("darkolivegreen1", Rgb::new(202, 255, 112)),
+    ("darkolivegreen2", Rgb::new(188, 238, 104)),
+    ("darkolivegreen3", Rgb::new(162, 205, 90)),
+    ("darkolivegreen4", Rgb::new(110, 139, 61)),
+    ("darkorange", Rgb::new(255, 140, 0)),
+    ("darkorange1", Rgb::new(255, 127, 0)),
+    ("darkorange2", Rgb::new(238, 118, 0)),
+    ("darkorange3", Rgb::new(205, 102, 0)),
+    ("darkorange4", Rgb::new(139, 69, 0)),
+    ("darkorchid", Rgb::new(153, 50, 204)),
+    ("darkorchid1", Rgb::new(191, 62, 255)),
+    ("darkorchid2", Rgb::new(178, 58, 238)),
+    ("darkorchid3", Rgb::new(154, 50, 205)),
+    ("darkorchid4", Rgb::new(104, 34, 139)),
+    ("darkred", Rgb::new(139, 0, 0)),
+    ("darksalmon", Rgb::new(233, 150, 122)),
+    ("darkseagreen", Rgb::new(143, 188, 143)),
+    ("darkseagreen1", Rgb::new(193, 255, 193)),
+    ("darkseagreen2", Rgb::new(180, 238, 180)),
+    ("darkseagreen3", Rgb::new(155, 205, 155)),
+    ("darkseagreen4", Rgb::new(105, 139, 105)),
+    ("darkslateblue", Rgb::new(72, 61, 139)),
+    ("darkslategray", Rgb::new(47, 79, 79)),
+    ("darkslategray1", Rgb::new(151, 255, 255)),
+    ("darkslategray2", Rgb::new(141, 238, 238)),
+    ("darkslategray3", Rgb::new(121, 205, 205)),
+    ("darkslategray4", Rgb::new(82, 139, 139)),
+    ("darkslategrey", Rgb::new(47, 79, 79)),
+    ("darkturquoise", Rgb::new(0, 206, 209)),
+    ("darkviolet", Rgb::new(148, 0, 211)),
+    ("debianred", Rgb::new(215, 7, 81)),
+    ("deeppink", Rgb::new(255, 20, 147)),
+    ("deeppink1", Rgb::new(255, 20, 147)),
+    ("deeppink2", Rgb::new(238, 18, 137)),
+    ("deeppink3", Rgb::new(205, 16, 118)),
+    ("deeppink4", Rgb::new(139, 10, 80)),
+    ("deepskyblue", Rgb::new(0, 191, 255)),
+    ("deepskyblue1", Rgb::new(0, 191, 255)),
+    ("deepskyblue2", Rgb::new(0, 178, 238)),
+    ("deepskyblue3", Rgb::new(0, 154, 205)),
+    ("deepskyblue4", Rgb::new(0, 104, 139)),
+    ("dimgray", Rgb::new(105, 105, 105)),
+    ("dimgrey", Rgb::new(105, 105, 105)),
+    ("dodgerblue", Rgb::new(30, 144, 255)),
+    ("dodgerblue1", Rgb::new(30, 144, 255)),
+    ("dodgerblue2", Rgb::new(28, 134, 238)),
+    ("dodgerblue3", Rgb::new(24, 116, 205)),
+    ("dodgerblue4", Rgb::new(16, 78, 139)),
+    ("firebrick", Rgb::new(178, 34, 34)),
+    ("firebrick1", Rgb::new(255, 48, 48)),
+    ("firebrick2", Rgb::new(238, 44, 44)),
+    ("firebrick3", Rgb::new(205, 38, 38)),
+    ("firebrick4", Rgb::new(139, 26, 26)),
+    ("floralwhite", Rgb::new(255, 250, 240)),
+    ("forestgreen", Rgb::new(34, 139, 34)),
+    ("gainsboro", Rgb::new(220, 220, 220)),
+    ("ghostwhite", Rgb::new(248, 248, 255)),
+    ("gold", Rgb::new(255, 215, 0)),
+    ("gold1", Rgb::new(255, 215, 0)),
+    ("gold2", Rgb::new(238, 201, 0)),
+    ("gold3", Rgb::new(205, 173, 0)),
+    ("gold4", Rgb::new(139, 117, 0)),
+    ("goldenrod", Rgb::new(218, 165, 32)),
+    ("goldenrod1", Rgb::new(255, 193, 37)),
+    ("goldenrod2", Rgb::new(238, 180, 34)),
+    ("goldenrod3", Rgb::new(205, 155, 29)),
+    ("goldenrod4", Rgb::new(139, 105, 20)),
+    ("gray", Rgb::new(190, 190, 190)),
+    ("gray0", Rgb::new(0, 0, 0)),
+    ("gray1", Rgb::new(3, 3, 3)),
+    ("gray10", Rgb::new(26, 26, 26)),
+    ("gray100", Rgb::new(255, 255, 255)),
+    ("gray11", Rgb::new(28, 28, 28)),
+    ("gray12", Rgb::new(31, 31, 31)),
+    ("gray13", Rgb::new(33, 33, 33)),
+    ("gray14", Rgb::new(36, 36, 36)),
+    ("gray15", Rgb::new(38, 38, 38)),
+    ("gray16", Rgb::new(41, 41, 41)),
+    ("gray17", Rgb::new(43, 43, 43)),
+    ("gray18", Rgb::new(46, 46, 46)),
+    ("gray19", Rgb::new(48, 48, 48)),
+    ("gray2", Rgb::new(5, 5, 5)),
+    ("gray20", Rgb::new(51, 51, 51)),
+    ("gray21", Rgb::new(54, 54, 54)),
+    ("gray22", Rgb::new(56, 56, 56)),
+    ("gray23", Rgb::new(59, 59, 59)),
+    ("gray24", Rgb::new(61, 61, 61)),
+    ("gray25", Rgb::new(64, 64, 64)),
+    ("gray26", Rgb::new(66, 66, 66)),
+    ("gray27", Rgb::new(69, 69, 69)),
+    ("gray28", Rgb::new(71, 71, 71)),
+    ("gray29", Rgb::new(74, 74, 74)),
+    ("gray3", Rgb::new(8, 8, 8)),
+    ("gray30", Rgb::new(77, 77, 77)),
+    ("gray31", Rgb::new(79, 79, 79)),
+    ("gray32", Rgb::new(82, 82, 82)),
+    ("gray33", Rgb::new(84, 84, 84)),
+    ("gray34", Rgb::new(87, 87, 87)),
+    ("gray35", Rgb::new(89, 89, 89)),
+    ("gray36", Rgb::new(92, 92, 92)),
+    ("gray37", Rgb::new(94, 94, 94)),
+    ("gray38", Rgb::new(97, 97, 97)),
+    ("gray39", Rgb::new(99, 99, 99)),
+    ("gray4", Rgb::new(10, 10, 10)),
+    ("gray40", Rgb::new(102, 102, 102)),
+    ("gray41", Rgb::new(105, 105, 105)),
+    ("gray42", Rgb::new(107, 107, 107)),
+    ("gray43", Rgb::new(110, 110, 110)),
+    ("gray44", Rgb::new(112, 112, 112)),
+    ("gray45", Rgb::new(115, 115, 115)),
+    ("gray46", Rgb::new(117, 117, 117)),
+    ("gray47", Rgb::new(120, 120, 120)),
+    ("gray48", Rgb::new(122, 122, 122)),
+    ("gray49", Rgb::new(125, 125, 125)),
+    ("gray5", Rgb::new(13, 13, 13)),
+    ("gray50", Rgb::new(127, 127, 127)),
+    ("gray51", Rgb::new(130, 130, 130)),
+    ("gray52", Rgb::new(133, 133, 133)),
+    ("gray53", Rgb::new(135, 135, 135)),
+    ("gray54", Rgb::new(138, 138, 138)),
+    ("gray55", Rgb::new(140, 140, 140)),
+    ("gray56", Rgb::new(143, 143, 143)),
+    ("gray57", Rgb::new(145, 145, 145)),
+    ("gray58", Rgb::new(148, 148, 148)),
+    ("gray59", Rgb::new(150, 150, 150)),
+    ("gray6", Rgb::new(15, 15, 15)),
+    ("gray60", Rgb::new(153, 153, 153)),
+    ("gray61", Rgb::new(156, 156, 156)),
+    ("gray62", Rgb::new(158, 158, 158)),
+    ("gray63", Rgb::new(161, 161, 161)),
+    ("gray64", Rgb::new(163, 163, 163)),
+    ("gray65", Rgb::new(166, 166, 166)),
+    ("gray66", Rgb::new(168, 168, 168)),
+    ("gray67", Rgb::new(171, 171, 171)),
+    ("gray68", Rgb::new(173, 173, 173)),
+    ("gray69", Rgb::new(176, 176, 176)),
+    ("gray7", Rgb::new(18, 18, 18)),
+    ("gray70", Rgb::new(179, 179, 179)),
+    ("gray71", Rgb::new(181, 181, 181)),
+    ("gray72", Rgb::new(184, 184, 184)),
+    ("gray73", Rgb::new(186, 186, 186)),
+    ("gray74", Rgb::new(189, 189, 189)),
+    ("gray75", Rgb::new(191, 191, 191)),
+    ("gray76", Rgb::new(194, 194, 194)),
+    ("gray77", Rgb::new(196, 196, 196)),
+    ("gray78", Rgb::new(199, 199, 199)),
+    ("gray79", Rgb::new(201, 201, 201)),
+    ("gray8", Rgb::new(20, 20, 20)),
+    ("gray80", Rgb::new(204, 204, 204)),
+    ("gray81", Rgb::new(207, 207, 207)),
+    ("gray82", Rgb::new(209, 209, 209)),
+    ("gray83", Rgb::new(212, 212, 212)),
+    ("gray84", Rgb::new(214, 214, 214)),
+    ("gray85", Rgb::new(217, 217, 217)),
+    ("gray86", Rgb::new(219, 219, 219)),
+    ("gray87", Rgb::new(222, 222, 222)),
+    ("gray88", Rgb::new(224, 224, 224)),
+    ("gray89", Rgb::new(227, 227, 227)),
+    ("gray9", Rgb::new(23, 23, 23)),
+    ("gray90", Rgb::new(229, 229, 229)),
+    ("gray91", Rgb::new(232, 232, 232)),
+    ("gray92", Rgb::new(235, 235, 235)),
+    ("gray93", Rgb::new(237, 237, 237)),
+    ("gray94", Rgb::new(240, 240, 240)),
+    ("gray95", Rgb::new(242, 242, 242)),
+    ("gray96", Rgb::new(245, 245, 245)),
+    ("gray97", Rgb::new(247, 247, 247)),
+    ("gray98", Rgb::new(250, 250, 250)),
+    ("gray99", Rgb::new(252, 252, 252)),
+    ("green", Rgb::new(0, 255, 0)),
+    ("green1", Rgb::new(0, 255, 0)),
+    ("green2", Rgb::new(0, 238, 0)),
+    ("green3", Rgb::new(0, 205, 0)),
+    ("green4", Rgb::new(0, 139, 0)),
+    ("greenyellow", Rgb::new(173, 255, 47)),
+    ("grey", Rgb::new(190, 190, 190)),
+    ("grey0", Rgb::new(0, 0, 0)),
+    ("grey1", Rgb::new(3, 3, 3)),
+    ("grey10", Rgb::new(26, 26, 26)),
+    ("grey100", Rgb::new(255, 255, 255)),
+    ("grey11", Rgb::new(28, 28, 28)),
+    ("grey12", Rgb::new(31, 31, 31)),
+    ("grey13", Rgb::new(33, 33, 33)),
+    ("grey14", Rgb::new(36, 36, 36)),
+    ("grey15", Rgb::new(38, 38, 38)),
+    ("grey16", Rgb::new(41, 41, 41)),
+    ("grey17", Rgb::new(43, 43, 43)),
+    ("grey18", Rgb::new(46, 46, 46)),
+    ("grey19", Rgb::new(48, 48, 48)),
+    ("grey2", Rgb::new(5, 5, 5)),
+    ("grey20", Rgb::new(51, 51, 51)),
+    ("grey21", Rgb::new(54, 54, 54)),
+    ("grey22", Rgb::new(56, 56, 56)),
+    ("grey23", Rgb::new(59, 59, 59)),
+    ("grey24", Rgb::new(61, 61, 61)),
+    ("grey25", Rgb::new(64, 64, 64)),
+    ("grey26", Rgb::new(66, 66, 66)),
+    ("grey27", Rgb::new(69, 69, 69)),
+    ("grey28", Rgb::new(71, 71, 71)),
+    ("grey29", Rgb::new(74, 74, 74)),
+    ("grey3", Rgb::new(8, 8, 8)),
+    ("grey30", Rgb::new(77, 77, 77)),
+    ("grey31", Rgb::new(79, 79, 79)),
+    ("grey32", Rgb::new(82, 82, 82)),
+    ("grey33", Rgb::new(84, 84, 84)),
+    ("grey34", Rgb::new(87, 87, 87)),
+    ("grey35", Rgb::new(89, 89, 89)),
+    ("grey36", Rgb::new(92, 92, 92)),
+    ("grey37", Rgb::new(94, 94, 94)),
+    ("grey38", Rgb::new(97, 97, 97)),
+    ("grey39", Rgb::new(99, 99, 99)),
+    ("grey4", Rgb::new(10, 10, 10)),
+    ("grey40", Rgb::new(102, 102, 102)),
+    ("grey41", Rgb::new(105, 105, 105)),
+    ("grey42", Rgb::new(107, 107, 107)),
+    ("grey43", Rgb::new(110, 110, 110)),
+    ("grey44", Rgb::new(112, 112, 112)),
+    ("grey45", Rgb::new(115, 115, 115)),
+    ("grey46", Rgb::new(117, 117, 117)),
+    ("grey47", Rgb::new(120, 120, 120)),
+    ("grey48", Rgb::new(122, 122, 122)),
+    ("grey49", Rgb::new(125, 125, 125)),
+    ("grey5", Rgb::new(13, 13, 13)),
+    ("grey50", Rgb::new(127, 127, 127)),
+    ("grey51", Rgb::new(130, 130, 130)),
+    ("grey52", Rgb::new(133, 133, 133)),
+    ("grey53", Rgb::new(135, 135, 135)),
+    ("grey54", Rgb::new(138, 138, 138)),
+    ("grey55", Rgb::new(140, 140, 140)),
+    ("grey56", Rgb::new(143, 143, 143)),
+    ("grey57", Rgb::new(145, 145, 145)),
+    ("grey58", Rgb::new(148, 148, 148)),
+    ("grey59", Rgb::new(150, 150, 150)),
+    ("grey6", Rgb::new(15, 15, 15)),
+    ("grey60", Rgb::new(153, 153, 153)),
+    ("grey61", Rgb::new(156, 156, 156)),
+    ("grey62", Rgb::new(158, 158, 158)),
+    ("grey63", Rgb::new(161, 161, 161)),
+    ("grey64", Rgb::new(163, 163, 163)),
+    ("grey65", Rgb::new(166, 166, 166)),
+    ("grey66", Rgb::new(168, 168, 168)),
+    ("grey67", Rgb::new(171, 171, 171)),
+    ("grey68", Rgb::new(173, 173, 173)),
+    ("grey69", Rgb::new(176, 176, 176)),
+    ("grey7", Rgb::new(18, 18, 18)),
+    ("grey70", Rgb::new(179, 179, 179)),
+    ("grey71", Rgb::new(181, 181, 181)),
+    ("grey72", Rgb::new(184, 184, 184)),
+    ("grey73", Rgb::new(186, 186, 186)),
+    ("grey74", Rgb::new(189, 189, 189)),
+    ("grey75", Rgb::new(191, 191, 191)),
+    ("grey76", Rgb::new(194, 194, 194)),
+    ("grey77", Rgb::new(196, 196, 196)),
+    ("grey78", Rgb::new(199, 199, 199)),
+    ("grey79", Rgb::new(201, 201, 201)),
+    ("grey8", Rgb::new(20, 20, 20)),
+    ("grey80", Rgb::new(204, 204, 204)),
+    ("grey81", Rgb::new(207, 207, 207)),
+    ("grey82", Rgb::new(209, 209, 209)),
+    ("grey83", Rgb::new(212, 212, 212)),
+    ("grey84", Rgb::new(214, 214, 214)),
+    ("grey85", Rgb::new(217, 217, 217)),
+    ("grey86", Rgb::new(219, 219, 219)),
+    ("grey87", Rgb::new(222, 222, 222)),
+    ("grey88", Rgb::new(224, 224, 224)),
+    ("grey89", Rgb::new(227, 227, 227)),
+    ("grey9", Rgb::new(23, 23, 23)),
+    ("grey90", Rgb::new(229, 229, 229)),
+    ("grey91", Rgb::new(232, 232, 232)),
+    ("grey92", Rgb::new(235, 235, 235)),
+    ("grey93", Rgb::new(237, 237, 237)),
+    ("grey94", Rgb::new(240, 240, 240)),
+    ("grey95", Rgb::new(242, 242, 242)),
+    ("grey96", Rgb::new(245, 245, 245)),
+    ("grey97", Rgb::new(247, 247, 247)),
+    ("grey98", Rgb::new(250, 250, 250)),
+    ("grey99", Rgb::new(252, 252, 252)),
+    ("honeydew", Rgb::new(240, 255, 240)),
+    ("honeydew1", Rgb::new(240, 255, 240)),
+    ("honeydew2", Rgb::new(224, 238, 224)),
+    ("honeydew3", Rgb::new(193, 205, 193)),
+    ("honeydew4", Rgb::new(131, 139, 131)),
+    ("hotpink", Rgb::new(255, 105, 180)),
+    ("hotpink1", Rgb::new(255, 110, 180)),
+    ("hotpink2", Rgb::new(238, 106, 167)),
+    ("hotpink3", Rgb::new(205, 96, 144)),
+    ("hotpink4", Rgb::new(139, 58, 98)),
+    ("indianred", Rgb::new(205, 92, 92)),
+    ("indianred1", Rgb::new(255, 106, 106)),
+    ("indianred2", Rgb::new(238, 99, 99)),
+    ("indianred3", Rgb::new(205, 85, 85)),
+    ("indianred4", Rgb::new(139, 58, 58)),
+    ("ivory", Rgb::new(255, 255, 240)),
+    ("ivory1", Rgb::new(255, 255, 240)),
+    ("ivory2", Rgb::new(238, 238, 224)),
+    ("ivory3", Rgb::new(205, 205, 193)),
+    ("ivory4", Rgb::new(139, 139, 131)),
+    ("khaki", Rgb::new(240, 230, 140)),
+    ("khaki1", Rgb::new(255, 246, 143)),
+    ("khaki2", Rgb::new(238, 230, 133)),
+    ("khaki3", Rgb::new(205, 198, 115)),
+    ("khaki4", Rgb::new(139, 134, 78)),
+    ("lavender", Rgb::new(230, 230, 250)),
+    ("lavenderblush", Rgb::new(255, 240, 245)),
+    ("lavenderblush1", Rgb::new(255, 240, 245)),
+    ("lavenderblush2", Rgb::new(238, 224, 229)),
+    ("lavenderblush3", Rgb::new(205, 193, 197)),
+    ("lavenderblush4", Rgb::new(139, 131, 134)),
+    ("lawngreen", Rgb::new(124, 252, 0)),
+    ("lemonchiffon", Rgb::new(255, 250, 205)),
+    ("lemonchiffon1", Rgb::new(255, 250, 205)),
+    ("lemonchiffon2", Rgb::new(238, 233, 191)),
+    ("lemonchiffon3", Rgb::new(205, 201, 165)),
+    ("lemonchiffon4", Rgb::new(139, 137, 112)),
+    ("lightblue", Rgb::new(173, 216, 230)),
+    ("lightblue1", Rgb::new(191, 239, 255)),
+    ("lightblue2", Rgb::new(178, 223, 238)),
+    ("lightblue3", Rgb::new(154, 192, 205)),
+    ("lightblue4", Rgb::new(104, 131, 139)),
+    ("lightcoral", Rgb::new(240, 128, 128)),
+    ("lightcyan", Rgb::new(224, 255, 255)),
+    ("lightcyan1", Rgb::new(224, 255, 255)),
+    ("lightcyan2", Rgb::new(209, 238, 238)),
+    ("lightcyan3", Rgb::new(180, 205, 205)),
+    ("lightcyan4", Rgb::new(122, 139, 139)),
+    ("lightgoldenrod", Rgb::new(238, 221, 130)),
+    ("lightgoldenrod1", Rgb::new(255, 236, 139)),
+    ("lightgoldenrod2", Rgb::new(238, 220, 130)),
+    ("lightgoldenrod3", Rgb::new(205, 190, 112)),
+    ("lightgoldenrod4", Rgb::new(139, 129, 76)),
+    ("lightgoldenrodyellow", Rgb::new(250, 250, 210)),
+    ("lightgray", Rgb::new(211, 211, 211)),
+    ("lightgreen", Rgb::new(144, 238, 144)),
+    ("lightgrey", Rgb::new(211, 211, 211)),
+    ("lightpink", Rgb::new(255, 182, 193)),
+    ("lightpink1", Rgb::new(255, 174, 185)),
+    ("lightpink2", Rgb::new(238, 162, 173)),
+    ("lightpink3", Rgb::new(205, 140, 149)),
+    ("lightpink4", Rgb::new(139, 95, 101)),
+    ("lightsalmon", Rgb::new(255, 160, 122)),
+    ("lightsalmon1", Rgb::new(255, 160, 122)),
+    ("lightsalmon2", Rgb::new(238, 149, 114)),
+    ("lightsalmon3", Rgb::new(205, 129, 98)),
+    ("lightsalmon4", Rgb::new(139, 87, 66)),
+    ("lightseagreen", Rgb::new(32, 178, 170)),
+    ("lightskyblue", Rgb::new(135, 206, 250)),
+    ("lightskyblue1", Rgb::new(176, 226, 255)),
+    ("lightskyblue2", Rgb::new(164, 211, 238)),
+    ("lightskyblue3", Rgb::new(141, 182, 205)),
+    ("lightskyblue4", Rgb::new(96, 123, 139)),
+    ("lightslateblue", Rgb::new(132, 112, 255)),
+    ("lightslategray", Rgb::new(119, 136, 153)),
+    ("lightslategrey", Rgb::new(119, 136, 153)),
+    ("lightsteelblue", Rgb::new(176, 196, 222)),
+    ("lightsteelblue1", Rgb::new(202, 225, 255)),
+    ("lightsteelblue2", Rgb::new(188, 210, 238)),
+    ("lightsteelblue3", Rgb::new(162, 181, 205)),
+    ("lightsteelblue4", Rgb::new(110, 123, 139)),
+    ("lightyellow", Rgb::new(255, 255, 224)),
+    ("lightyellow1", Rgb::new(255, 255, 224)),
+    ("lightyellow2", Rgb::new(238, 238, 209)),
+    ("lightyellow3", Rgb::new(205, 205, 180)),
+    ("lightyellow4", Rgb::new(139, 139, 122)),
+    ("limegreen", Rgb::new(50, 205, 50)),
+    ("linen", Rgb::new(250, 240, 230)),
+    ("magenta", Rgb::new(255, 0, 255)),
+    ("magenta1", Rgb::new(255, 0, 255)),
+    ("magenta2", Rgb::new(238, 0, 238)),
+    ("magenta3", Rgb::new(205, 0, 205)),
+    ("magenta4", Rgb::new(139, 0, 139)),
+    ("maroon", Rgb::new(176, 48, 96)),
+    ("maroon1", Rgb::new(255, 52, 179)),
+    ("maroon2", Rgb::new(238, 48, 167)),
+    ("maroon3", Rgb::new(205, 41, 144)),
+    ("maroon4", Rgb::new(139, 28, 98)),
+    ("mediumaquamarine", Rgb::new(102, 205, 170)),
+    ("mediumblue", Rgb::new(0, 0, 205)),
+    ("mediumorchid", Rgb::new(186, 85, 211)),
+    ("mediumorchid1", Rgb::new(224, 102, 255)),
+    ("mediumorchid2", Rgb::new(209, 95, 238)),
+    ("mediumorchid3", Rgb::new(180, 82, 205)),
+    ("mediumorchid4", Rgb::new(122, 55, 139)),
+    ("mediumpurple", Rgb::new(147, 112, 219)),
+    ("mediumpurple1", Rgb::new(171, 130, 255)),
+    ("mediumpurple2", Rgb::new(159, 121, 238)),
+    ("mediumpurple3", Rgb::new(137, 104, 205)),
+    ("mediumpurple4", Rgb::new(93, 71, 139)),
+    ("mediumseagreen", Rgb::new(60, 179, 113)),
+    ("mediumslateblue", Rgb::new(123, 104, 238)),
+    ("mediumspringgreen", Rgb::new(0, 250, 154)),
+    ("mediumturquoise", Rgb::new(72, 209, 204)),
+    ("mediumvioletred", Rgb::new(199, 21, 133)),
+    ("midnightblue", Rgb::new(25, 25, 112)),
+    ("mintcream", Rgb::new(245, 255, 250)),
+    ("mistyrose", Rgb::new(255, 228, 225)),
+    ("mistyrose1", Rgb::new(255, 228, 225)),
+    ("mistyrose2", Rgb::new(238, 213, 210)),
+    ("mistyrose3", Rgb::new(205, 183, 181)),
+    ("mistyrose4", Rgb::new(139, 125, 123)),
+    ("moccasin", Rgb::new(255, 228, 181)),
+    ("navajowhite", Rgb::new(255, 222, 173)),
+    ("navajowhite1", Rgb::new(255, 222, 173)),
+    ("navajowhite2", Rgb::new(238, 207, 161)),
+    ("navajowhite3", Rgb::new(205, 179, 139)),
+    ("navajowhite4", Rgb::new(139, 121, 94)),
+    ("navy", Rgb::new(0, 0, 128)),
+    ("navyblue", Rgb::new(0, 0, 128)),
+    ("oldlace", Rgb::new(253, 245, 230)),
+    ("olivedrab", Rgb::new(107, 142, 35)),
+    ("olivedrab1", Rgb::new(192, 255, 62)),
+    ("olivedrab2", Rgb::new(179, 238, 58)),
+    ("olivedrab3", Rgb::new(154, 205, 50)),
+    ("olivedrab4", Rgb::new(105, 139, 34)),
+    ("orange", Rgb::new(255, 165, 0)),
+    ("orange1", Rgb::new(255, 165, 0)),
+    ("orange2", Rgb::new(238, 154, 0)),
+    ("orange3", Rgb::new(205, 133, 0)),
+    ("orange4", Rgb::new(139, 90, 0)),
+    ("orangered", Rgb::new(255, 69, 0)),
+    ("orangered1", Rgb::new(255, 69, 0)),
+    ("orangered2", Rgb::new(238, 64, 0)),
+    ("orangered3", Rgb::new(205, 55, 0)),
+    ("orangered4", Rgb::new(139, 37, 0)),
+    ("orchid", Rgb::new(218, 112, 214)),
+    ("orchid1", Rgb::new(255, 131, 250)),
+    ("orchid2", Rgb::new(238, 122, 233)),
+    ("orchid3", Rgb::new(205, 105, 201)),
+    ("orchid4", Rgb::new(139, 71, 137)),
+    ("palegoldenrod", Rgb::new(238, 232, 170)),
+    ("palegreen", Rgb::new(152, 251, 152)),
+    ("palegreen1", Rgb::new(154, 255, 154)),
+    ("palegreen2", Rgb::new(144, 238, 144)),
+    ("palegreen3", Rgb::new(124, 205, 124)),
+    ("palegreen4", Rgb::new(84, 139, 84)),
+    ("paleturquoise", Rgb::new(175, 238, 238)),
+    ("paleturquoise1", Rgb::new(187, 255, 255)),
+    ("paleturquoise2", Rgb::new(174, 238, 238)),
+    ("paleturquoise3", Rgb::new(150, 205, 205)),
+    ("paleturquoise4", Rgb::new(102, 139, 139)),
+    ("palevioletred", Rgb::new(219, 112, 147)),
+    ("palevioletred1", Rgb::new(255, 130, 171)),
+    ("palevioletred2", Rgb::new(238, 121, 159)),
+    ("palevioletred3", Rgb::new(205, 104, 137)),
+    ("palevioletred4", Rgb::new(139, 71, 93)),
+    ("papayawhip", Rgb::new(255, 239, 213)),
+    ("peachpuff", Rgb::new(255, 218, 185)),
+    ("peachpuff1", Rgb::new(255, 218, 185)),
+    ("peachpuff2", Rgb::new(238, 203, 173)),
+    ("peachpuff3", Rgb::new(205, 175, 149)),
+    ("peachpuff4", Rgb::new(139, 119, 101)),
+    ("peru", Rgb::new(205, 133, 63)),
+    ("pink", Rgb::new(255, 192, 203)),
+    ("pink1", Rgb::new(255, 181, 197)),
+    ("pink2", Rgb::new(238, 169, 184)),
+    ("pink3", Rgb::new(205, 145, 158)),
+    ("pink4", Rgb::new(139, 99, 108)),
+    ("plum", Rgb::new(221, 160, 221)),
+    ("plum1", Rgb::new(255, 187, 255)),
+    ("plum2", Rgb::new(238, 174, 238)),
+    ("plum3", Rgb::new(205, 150, 205)),
+    ("plum4", Rgb::new(139, 102, 139)),
+    ("powderblue", Rgb::new(176, 224, 230)),
+    ("purple", Rgb::new(160, 32, 240)),
+    ("purple1", Rgb::new(155, 48, 255)),
+    ("purple2", Rgb::new(145, 44, 238)),
+    ("purple3", Rgb::new(125, 38, 205)),
+    ("purple4", Rgb::new(85, 26, 139)),
+    ("red", Rgb::new(255, 0, 0)),
+    ("red1", Rgb::new(255, 0, 0)),
+    ("red2", Rgb::new(238, 0, 0)),
+    ("red3", Rgb::new(205, 0, 0)),
+    ("red4", Rgb::new(139, 0, 0)),
+    ("rosybrown", Rgb::new(188, 143, 143)),
+    ("rosybrown1", Rgb::new(255, 193, 193)),
+    ("rosybrown2", Rgb::new(238, 180, 180)),
+    ("rosybrown3", Rgb::new(205, 155, 155)),
+    ("rosybrown4", Rgb::new(139, 105, 105)),
+    ("royalblue", Rgb::new(65, 105, 225)),
+    ("royalblue1", Rgb::new(72, 118, 255)),
+    ("royalblue2", Rgb::new(67, 110, 238)),
+    ("royalblue3", Rgb::new(58, 95, 205)),
+    ("royalblue4", Rgb::new(39, 64, 139)),
+    ("saddlebrown", Rgb::new(139, 69, 19)),
+    ("salmon", Rgb::new(250, 128, 114)),
+    ("salmon1", Rgb::new(255, 140, 105)),
+    ("salmon2", Rgb::new(238, 130, 98)),
+    ("salmon3", Rgb::new(205, 112, 84)),
+    ("salmon4", Rgb::new(139, 76, 57)),
+    ("sandybrown", Rgb::new(244, 164, 96)),
+    ("seagreen", Rgb::new(46, 139, 87)),
+    ("seagreen1", Rgb::new(84, 255, 159)),
+    ("seagreen2", Rgb::new(78, 238, 148)),
+    ("seagreen3", Rgb::new(67, 205, 128)),
+    ("seagreen4", Rgb::new(46, 139, 87)),
+    ("seashell", Rgb::new(255, 245, 238)),
+    ("seashell1", Rgb::new(255, 245, 238)),
+    ("seashell2", Rgb::new(238, 229, 222)),
+    ("seashell3", Rgb::new(205, 197, 191)),
+    ("seashell4", Rgb::new(139, 134, 130)),
+    ("sienna", Rgb::new(160, 82, 45)),
+    ("sienna1", Rgb::new(255, 130, 71)),
+    ("sienna2", Rgb::new(238, 121, 66)),
+    ("sienna3", Rgb::new(205, 104, 57)),
+    ("sienna4", Rgb::new(139, 71, 38)),
+    ("skyblue", Rgb::new(135, 206, 235)),
+    ("skyblue1", Rgb::new(135, 206, 255)),
+    ("skyblue2", Rgb::new(126, 192, 238)),
+    ("skyblue3", Rgb::new(108, 166, 205)),
+    ("skyblue4", Rgb::new(74, 112, 139)),
+    ("slateblue", Rgb::new(106, 90, 205)),
+    ("slateblue1", Rgb::new(131, 111, 255)),
+    ("slateblue2", Rgb::new(122, 103, 238)),
+    ("slateblue3", Rgb::new(105, 89, 205)),
+    ("slateblue4", Rgb::new(71, 60, 139)),
+    ("slategray", Rgb::new(112, 128, 144)),
+    ("slategray1", Rgb::new(198, 226, 255)),
+    ("slategray2", Rgb::new(185, 211, 238)),
+    ("slategray3", Rgb::new(159, 182, 205)),
+    ("slategray4", Rgb::new(108, 123, 139)),
+    ("slategrey", Rgb::new(112, 128, 144)),
+    ("snow", Rgb::new(255, 250, 250)),
+    ("snow1", Rgb::new(255, 250, 250)),
+    ("snow2", Rgb::new(238, 233, 233)),
+    ("snow3", Rgb::new(205, 201, 201)),
+    ("snow4", Rgb::new(139, 137, 137)),
+    ("springgreen", Rgb::new(0, 255, 127)),
+    ("springgreen1", Rgb::new(0, 255, 127)),
+    ("springgreen2", Rgb::new(0, 238, 118)),
+    ("springgreen3", Rgb::new(0, 205, 102)),
+    ("springgreen4", Rgb::new(0, 139, 69)),
+    ("steelblue", Rgb::new(70, 130, 180)),
+    ("steelblue1", Rgb::new(99, 184, 255)),
+    ("steelblue2", Rgb::new(92, 172, 238)),
+    ("steelblue3", Rgb::new(79, 148, 205)),
+    ("steelblue4", Rgb::new(54, 100, 139)),
+    ("tan", Rgb::new(210, 180, 140)),
+    ("tan1", Rgb::new(255, 165, 79)),
+    ("tan2", Rgb::new(238, 154, 73)),
+    ("tan3", Rgb::new(205, 133, 63)),
+    ("tan4", Rgb::new(139, 90, 43)),
+    ("thistle", Rgb::new(216, 191, 216)),
+    ("thistle1", Rgb::new(255, 225, 255)),
+    ("thistle2", Rgb::new(238, 210, 238)),
+    ("thistle3", Rgb::new(205, 181, 205)),
+    ("thistle4", Rgb::new(139, 123, 139)),
+    ("tomato", Rgb::new(255, 99, 71)),
+    ("tomato1", Rgb::new(255, 99, 71)),
+    ("tomato2", Rgb::new(238, 92, 66)),
+    ("tomato3", Rgb::new(205, 79, 57)),
+    ("tomato4", Rgb::new(139, 54, 38)),
+    ("turquoise", Rgb::new(64, 224, 208)),
+    ("turquoise1", Rgb::new(0, 245, 255)),
+    ("turquoise2", Rgb::new(0, 229, 238)),
+    ("turquoise3", Rgb::new(0, 197, 205)),
+    ("turquoise4", Rgb::new(0, 134, 139)),
+    ("violet", Rgb::new(238, 130, 238)),
+    ("violetred", Rgb::new(208, 32, 144)),
+    ("violetred1", Rgb::new(255, 62, 150)),
+    ("violetred2", Rgb::new(238, 58, 140)),
+    ("violetred3", Rgb::new(205, 50, 120)),
+    ("violetred4", Rgb::new(139, 34, 82)),
+    ("wheat", Rgb::new(245, 222, 179)),
+    ("wheat1", Rgb::new(255, 231, 186)),
+    ("wheat2", Rgb::new(238, 216, 174)),
+    ("wheat3", Rgb::new(205, 186, 150)),
+    ("wheat4", Rgb::new(139, 126, 102)),
+    ("white", Rgb::new(255, 255, 255)),
+    ("whitesmoke", Rgb::new(245, 245, 245)),
+    ("yellow", Rgb::new(255, 255, 0)),
+    ("yellow1", Rgb::new(255, 255, 0)),
+    ("yellow2", Rgb::new(238, 238, 0)),
+    ("yellow3", Rgb::new(205, 205, 0)),
+    ("yellow4", Rgb::new(139, 139, 0)),
+    ("yellowgreen", Rgb::new(154, 205, 50)),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_lowercase_and_unique() {
+        for (name, _) in X11_COLORS.iter() {
+            assert!(
+                name.chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit()),
+                "{:?} is not normalized",
+                name
+            );
+        }
+
+        for pair in X11_COLORS.windows(2) {
+            assert!(pair[0].0 < pair[1].0, "{:?} is out of order", pair[1].0);
+        }
+    }
+}
@@ -0,0 +1,627 @@
+use std::io::Write;
+
+use crate::{
+    backend::{Backend, TtyBackend},
+    command::Command,
+    events::EventBatch,
+    geometry::{Position, Size},
+    mode::Mode,
+    style::Rgb,
+    utils::PaletteRecord,
+};
+
+/// Modes which are enabled when mouse capture is enabled, in order
+const MOUSE_MODES: [Mode; 4] = [
+    Mode::MOUSE_CLICKS,
+    Mode::MOUSE_DRAG,
+    Mode::MOUSE_MOTION,
+    Mode::SGR_MOUSE,
+];
+
+/// The modes a `Terminal` has changed. Everything that differs from the default is undone when the
+/// terminal is restored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TerminalState {
+    pub raw_mode: bool,
+    pub alternate_buffer: bool,
+    pub mouse_capture: bool,
+    pub bracketed_paste: bool,
+    pub focus_events: bool,
+    pub cursor_visible: bool,
+}
+
+impl Default for TerminalState {
+    fn default() -> Self {
+        Self {
+            raw_mode: false,
+            alternate_buffer: false,
+            mouse_capture: false,
+            bracketed_paste: false,
+            focus_events: false,
+            cursor_visible: true,
+        }
+    }
+}
+
+/// Configuration applied when a `Terminal` is created
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct TerminalBuilder {
+    state: TerminalState,
+}
+
+impl TerminalBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn raw_mode(mut self, enabled: bool) -> Self {
+        self.state.raw_mode = enabled;
+        self
+    }
+
+    pub fn alternate_buffer(mut self, enabled: bool) -> Self {
+        self.state.alternate_buffer = enabled;
+        self
+    }
+
+    /// Mouse reports are consumed and delivered as `Event::UnrecognizedControlSequence` for now
+    pub fn mouse_capture(mut self, enabled: bool) -> Self {
+        self.state.mouse_capture = enabled;
+        self
+    }
+
+    pub fn bracketed_paste(mut self, enabled: bool) -> Self {
+        self.state.bracketed_paste = enabled;
+        self
+    }
+
+    pub fn focus_events(mut self, enabled: bool) -> Self {
+        self.state.focus_events = enabled;
+        self
+    }
+
+    pub fn cursor_visible(mut self, visible: bool) -> Self {
+        self.state.cursor_visible = visible;
+        self
+    }
+
+    /// Creates a terminal on stdout and stdin
+    pub fn build(self) -> std::io::Result<Terminal> {
+        self.build_with(TtyBackend::new())
+    }
+
+    /// Creates a terminal on any backend
+    pub fn build_with<B>(self, backend: B) -> std::io::Result<Terminal<B>>
+    where
+        B: Backend,
+    {
+        let mut terminal = Terminal::with_backend(backend);
+
+        terminal.apply(self.state)?;
+        terminal.flush()?;
+
+        Ok(terminal)
+    }
+}
+
+/// A terminal owning a backend, by default stdout and stdin with buffered output.
+///
+/// The terminal keeps track of the modes and palette entries it changed and undoes them when it
+/// is restored or dropped, so an application returns the terminal to the user as it found it even
+/// when exiting early. Writes are buffered until `flush` or `execute` is called.
+pub struct Terminal<B = TtyBackend>
+where
+    B: Backend,
+{
+    backend: B,
+    state: TerminalState,
+    palette: PaletteRecord,
+}
+
+impl Terminal {
+    /// Creates a terminal on stdout and stdin without changing any modes
+    pub fn new() -> Self {
+        Self::with_backend(TtyBackend::new())
+    }
+
+    /// Returns a builder for configuring the terminal on creation
+    pub fn builder() -> TerminalBuilder {
+        TerminalBuilder::new()
+    }
+}
+
+impl Default for Terminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<B> Terminal<B>
+where
+    B: Backend,
+{
+    /// Creates a terminal on any backend without changing any modes
+    pub fn with_backend(backend: B) -> Self {
+        Self {
+            backend,
+            state: TerminalState::default(),
+            palette: PaletteRecord::new(),
+        }
+    }
+
+    /// Returns the modes the terminal has changed
+    pub fn state(&self) -> TerminalState {
+        self.state
+    }
+
+    /// Returns the palette entries the terminal has changed
+    pub fn palette(&self) -> &PaletteRecord {
+        &self.palette
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Returns the size of the terminal
+    pub fn size(&self) -> std::io::Result<Size> {
+        self.backend.size()
+    }
+
+    /// Returns the position of the cursor, see `Backend::cursor_position`
+    pub fn cursor_position(&mut self) -> std::io::Result<Option<Position>> {
+        self.backend.cursor_position()
+    }
+
+    /// Writes a command without flushing
+    pub fn queue(&mut self, command: impl Command) -> std::io::Result<()> {
+        command.write_ansi(&mut self.backend)
+    }
+
+    /// Writes a command and flushes the output
+    pub fn execute(&mut self, command: impl Command) -> std::io::Result<()> {
+        command.write_ansi(&mut self.backend)?;
+
+        self.backend.flush()
+    }
+
+    /// Reads the available events without blocking
+    pub fn read_batch(&mut self) -> std::io::Result<EventBatch> {
+        self.backend.read_batch()
+    }
+
+    /// Reads events, waiting up to `timeout` milliseconds for the first one
+    pub fn read_batch_blocking(&mut self, timeout: u32) -> std::io::Result<EventBatch> {
+        self.backend.read_batch_blocking(timeout)
+    }
+
+    pub fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+        if !self.state.raw_mode {
+            self.backend.enable_raw_mode()?;
+            self.state.raw_mode = true;
+        }
+
+        Ok(())
+    }
+
+    pub fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+        if self.state.raw_mode {
+            self.backend.disable_raw_mode()?;
+            self.state.raw_mode = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn enter_alternate_buffer(&mut self) -> std::io::Result<()> {
+        if !self.state.alternate_buffer {
+            self.backend.set_mode(Mode::ALTERNATE_BUFFER)?;
+            self.state.alternate_buffer = true;
+        }
+
+        Ok(())
+    }
+
+    pub fn leave_alternate_buffer(&mut self) -> std::io::Result<()> {
+        if self.state.alternate_buffer {
+            self.backend.reset_mode(Mode::ALTERNATE_BUFFER)?;
+            self.state.alternate_buffer = false;
+        }
+
+        Ok(())
+    }
+
+    /// Enables reporting of mouse presses, releases and motion
+    pub fn enable_mouse_capture(&mut self) -> std::io::Result<()> {
+        if !self.state.mouse_capture {
+            for mode in MOUSE_MODES {
+                self.backend.set_mode(mode)?;
+            }
+
+            self.state.mouse_capture = true;
+        }
+
+        Ok(())
+    }
+
+    pub fn disable_mouse_capture(&mut self) -> std::io::Result<()> {
+        if self.state.mouse_capture {
+            for mode in MOUSE_MODES.into_iter().rev() {
+                self.backend.reset_mode(mode)?;
+            }
+
+            self.state.mouse_capture = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn enable_bracketed_paste(&mut self) -> std::io::Result<()> {
+        if !self.state.bracketed_paste {
+            self.backend.set_mode(Mode::BRACKETED_PASTE)?;
+            self.state.bracketed_paste = true;
+        }
+
+        Ok(())
+    }
+
+    pub fn disable_bracketed_paste(&mut self) -> std::io::Result<()> {
+        if self.state.bracketed_paste {
+            self.backend.reset_mode(Mode::BRACKETED_PASTE)?;
+            self.state.bracketed_paste = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn enable_focus_events(&mut self) -> std::io::Result<()> {
+        if !self.state.focus_events {
+            self.backend.set_mode(Mode::FOCUS_EVENTS)?;
+            self.state.focus_events = true;
+        }
+
+        Ok(())
+    }
+
+    pub fn disable_focus_events(&mut self) -> std::io::Result<()> {
+        if self.state.focus_events {
+            self.backend.reset_mode(Mode::FOCUS_EVENTS)?;
+            self.state.focus_events = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn hide_cursor(&mut self) -> std::io::Result<()> {
+        if self.state.cursor_visible {
+            self.backend.reset_mode(Mode::CURSOR_VISIBLE)?;
+            self.state.cursor_visible = false;
+        }
+
+        Ok(())
+    }
+
+    pub fn show_cursor(&mut self) -> std::io::Result<()> {
+        if !self.state.cursor_visible {
+            self.backend.set_mode(Mode::CURSOR_VISIBLE)?;
+            self.state.cursor_visible = true;
+        }
+
+        Ok(())
+    }
+
+    /// Changes an entry of the 256 color palette, which is reset to the terminal default when the
+    /// terminal is restored. Nothing is flushed
+    pub fn set_palette_color(&mut self, index: u8, rgb: Rgb) -> std::io::Result<()> {
+        self.palette.set(&mut self.backend, index, rgb)
+    }
+
+    /// Changes every mode to match `state`. Nothing is flushed
+    pub fn apply(&mut self, state: TerminalState) -> std::io::Result<()> {
+        if state.raw_mode {
+            self.enable_raw_mode()?;
+        } else {
+            self.disable_raw_mode()?;
+        }
+
+        if state.alternate_buffer {
+            self.enter_alternate_buffer()?;
+        } else {
+            self.leave_alternate_buffer()?;
+        }
+
+        if state.mouse_capture {
+            self.enable_mouse_capture()?;
+        } else {
+            self.disable_mouse_capture()?;
+        }
+
+        if state.bracketed_paste {
+            self.enable_bracketed_paste()?;
+        } else {
+            self.disable_bracketed_paste()?;
+        }
+
+        if state.focus_events {
+            self.enable_focus_events()?;
+        } else {
+            self.disable_focus_events()?;
+        }
+
+        if state.cursor_visible {
+            self.show_cursor()
+        } else {
+            self.hide_cursor()
+        }
+    }
+
+    /// Undoes every mode and palette change and flushes the output. This is also done when the
+    /// terminal is dropped, where errors are ignored.
+    ///
+    /// Every step is attempted even if an earlier one fails, so raw mode is left even when the
+    /// output is gone. The first error is returned
+    pub fn restore(&mut self) -> std::io::Result<()> {
+        let results = [
+            self.palette.restore(&mut self.backend),
+            self.show_cursor(),
+            self.disable_focus_events(),
+            self.disable_bracketed_paste(),
+            self.disable_mouse_capture(),
+            self.leave_alternate_buffer(),
+            self.backend.flush(),
+            self.disable_raw_mode(),
+        ];
+
+        results.into_iter().collect()
+    }
+}
+
+impl<B> Write for Terminal<B>
+where
+    B: Backend,
+{
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.backend.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.backend.flush()
+    }
+}
+
+impl<B> Drop for Terminal<B>
+where
+    B: Backend,
+{
+    fn drop(&mut self) {
+        let _ = self.restore();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        backend::TestBackend,
+        command::{MoveCursor, Print},
+        events::Event,
+    };
+
+    fn backend() -> TestBackend {
+        TestBackend::new(Size::new(24, 80))
+    }
+
+    /// A backend whose output can be made to fail, like a closed terminal
+    struct FailingBackend {
+        inner: TestBackend,
+        failing: bool,
+    }
+
+    impl FailingBackend {
+        fn check(&self, operation: &str) -> std::io::Result<()> {
+            if self.failing {
+                return Err(std::io::Error::other(format!("{} failed", operation)));
+            }
+
+            Ok(())
+        }
+    }
+
+    impl Write for FailingBackend {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.check("write")?;
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.check("flush")?;
+            self.inner.flush()
+        }
+    }
+
+    impl Backend for FailingBackend {
+        fn size(&self) -> std::io::Result<Size> {
+            self.inner.size()
+        }
+
+        fn cursor_position(&mut self) -> std::io::Result<Option<Position>> {
+            self.inner.cursor_position()
+        }
+
+        fn read_batch(&mut self) -> std::io::Result<EventBatch> {
+            self.inner.read_batch()
+        }
+
+        fn read_batch_blocking(&mut self, timeout: u32) -> std::io::Result<EventBatch> {
+            self.inner.read_batch_blocking(timeout)
+        }
+
+        fn enable_raw_mode(&mut self) -> std::io::Result<()> {
+            self.inner.enable_raw_mode()
+        }
+
+        fn disable_raw_mode(&mut self) -> std::io::Result<()> {
+            self.inner.disable_raw_mode()
+        }
+
+        fn set_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+            self.check("set_mode")?;
+            self.inner.set_mode(mode)
+        }
+
+        fn reset_mode(&mut self, mode: Mode) -> std::io::Result<()> {
+            self.check("reset_mode")?;
+            self.inner.reset_mode(mode)
+        }
+    }
+
+    #[test]
+    fn builder_sets_modes() {
+        let terminal = Terminal::builder()
+            .raw_mode(true)
+            .alternate_buffer(true)
+            .mouse_capture(true)
+            .bracketed_paste(true)
+            .focus_events(true)
+            .cursor_visible(false)
+            .build_with(backend())
+            .unwrap();
+
+        assert_eq!(
+            terminal.state(),
+            TerminalState {
+                raw_mode: true,
+                alternate_buffer: true,
+                mouse_capture: true,
+                bracketed_paste: true,
+                focus_events: true,
+                cursor_visible: false,
+            }
+        );
+
+        let backend = terminal.backend();
+
+        assert!(backend.is_raw_mode());
+        assert!(backend.is_mode_set(Mode::SGR_MOUSE));
+        assert!(backend.pending().is_empty());
+        assert_eq!(
+            backend.flushed(),
+            b"\x1b[?1049h\x1b[?1000h\x1b[?1002h\x1b[?1003h\x1b[?1006h\x1b[?2004h\x1b[?1004h\x1b[?25l"
+        );
+    }
+
+    #[test]
+    fn modes_are_changed_once() {
+        let mut terminal = Terminal::with_backend(backend());
+
+        terminal.enter_alternate_buffer().unwrap();
+        terminal.enter_alternate_buffer().unwrap();
+        terminal.hide_cursor().unwrap();
+        terminal.hide_cursor().unwrap();
+        terminal.show_cursor().unwrap();
+        terminal.show_cursor().unwrap();
+        terminal.disable_bracketed_paste().unwrap();
+        terminal.flush().unwrap();
+
+        assert_eq!(
+            terminal.backend_mut().take_flushed(),
+            b"\x1b[?1049h\x1b[?25l\x1b[?25h"
+        );
+    }
+
+    #[test]
+    fn restores_modes_on_drop() {
+        let mut backend = backend();
+
+        let mut terminal = Terminal::builder()
+            .raw_mode(true)
+            .alternate_buffer(true)
+            .bracketed_paste(true)
+            .cursor_visible(false)
+            .build_with(&mut backend)
+            .unwrap();
+
+        terminal.backend_mut().take_flushed();
+        terminal.queue(MoveCursor(Position::ORIGIN)).unwrap();
+        terminal.queue(Print("frame")).unwrap();
+
+        drop(terminal);
+
+        assert!(!backend.is_raw_mode());
+        assert!(!backend.is_mode_set(Mode::ALTERNATE_BUFFER));
+        assert_eq!(
+            backend.flushed(),
+            b"\x1b[1;1Hframe\x1b[?25h\x1b[?2004l\x1b[?1049l"
+        );
+    }
+
+    #[test]
+    fn restores_palette_on_drop() {
+        let mut backend = backend();
+        let mut terminal = Terminal::with_backend(&mut backend);
+
+        terminal.set_palette_color(1, Rgb::new(255, 0, 0)).unwrap();
+        terminal
+            .set_palette_color(200, Rgb::new(0, 0, 255))
+            .unwrap();
+        terminal.set_palette_color(1, Rgb::new(0, 255, 0)).unwrap();
+
+        assert_eq!(terminal.palette().changed().collect::<Vec<_>>(), [1, 200]);
+
+        drop(terminal);
+
+        assert_eq!(
+            backend.flushed(),
+            b"\x1b]4;1;rgb:ff/00/00\x1b\\\x1b]4;200;rgb:00/00/ff\x1b\\\x1b]4;1;rgb:00/ff/00\x1b\\\
+              \x1b]104;1\x1b\\\x1b]104;200\x1b\\"
+        );
+    }
+
+    #[test]
+    fn restore_leaves_raw_mode_when_output_fails() {
+        let backend = FailingBackend {
+            inner: backend(),
+            failing: false,
+        };
+
+        let mut terminal = Terminal::builder()
+            .raw_mode(true)
+            .alternate_buffer(true)
+            .cursor_visible(false)
+            .build_with(backend)
+            .unwrap();
+
+        terminal.backend_mut().failing = true;
+
+        let error = terminal.restore().unwrap_err();
+
+        // The first failing step is showing the cursor
+        assert_eq!(error.to_string(), "set_mode failed");
+        assert!(!terminal.backend().inner.is_raw_mode());
+        assert!(!terminal.state().raw_mode);
+        assert!(terminal.state().alternate_buffer);
+    }
+
+    #[test]
+    fn resizes_are_reported() {
+        let mut terminal = Terminal::with_backend(backend());
+
+        terminal.backend_mut().resize(Size::new(10, 40));
+        terminal.backend_mut().resize(Size::new(12, 50));
+
+        assert_eq!(terminal.size().unwrap(), Size::new(12, 50));
+
+        let events: Vec<Event> = terminal.read_batch().unwrap().into_iter().collect();
+        assert_eq!(
+            events,
+            [
+                Event::Resize(Size::new(10, 40)),
+                Event::Resize(Size::new(12, 50))
+            ]
+        );
+    }
+}
@@ -1,12 +1,24 @@
 use std::io::{Read, Write};
 
 use crate::{
-    events::{Event, EventBatch},
-    style::{BackgroundCode, ColorCode, ForegroundCode},
+    capabilities::Capabilities,
+    events::{DynamicColor, Event, EventBatch},
+    geometry::{Position, Size},
+    mode::{Mode, ModeStatus},
+    style::{
+        Attribute, Attributes, BackgroundCode, ColorCode, ColorScheme, ForegroundCode, Rgb,
+        UnderlineCode,
+    },
 };
 
 use self::platform::RawOs;
 
+mod cursor;
+mod palette;
+
+pub use cursor::CursorShape;
+pub use palette::PaletteRecord;
+
 #[cfg(target_family = "windows")]
 pub mod windows;
 
@@ -94,7 +106,7 @@ where
 pub fn get_cursor_position<Output, Input>(
     output: &mut Output,
     input: &mut Input,
-) -> std::io::Result<Option<(u16, u16)>>
+) -> std::io::Result<Option<Position>>
 where
     Output: Write,
     Input: platform::RawOs + Read,
@@ -102,12 +114,215 @@ where
     platform::get_cursor_position(output, input)
 }
 
-/// This function moves the cursor to a given position.
-pub fn move_cursor<Output>(output: &mut Output, line: u16, column: u16) -> std::io::Result<()>
+/// This function sets a terminal mode. Both DEC private modes and standard ANSI modes are
+/// supported, see `Mode` for commonly used values.
+pub fn set_mode<Output>(output: &mut Output, mode: Mode) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_mode(output, mode)
+}
+
+/// This function resets a terminal mode. Both DEC private modes and standard ANSI modes are
+/// supported, see `Mode` for commonly used values.
+pub fn reset_mode<Output>(output: &mut Output, mode: Mode) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_mode(output, mode)
+}
+
+/// This function queries the state of a terminal mode using DECRQM. Like `get_cursor_position`
+/// this requires the application be in raw mode and can block for up to 1 second. `None` is
+/// returned if the terminal does not reply at all, which is the case for terminals without
+/// DECRQM support.
+pub fn query_mode<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    mode: Mode,
+) -> std::io::Result<Option<ModeStatus>>
+where
+    Output: Write,
+    Input: platform::RawOs + Read,
+{
+    platform::query_mode(output, input, mode)
+}
+
+/// This function checks whether the terminal supports synchronized output (mode 2026) using
+/// `query_mode`. Terminals that do not reply, or report the mode as not recognized or permanently
+/// reset, are treated as unsupported. The result is intended to be queried once and passed to
+/// `synchronized_update`.
+pub fn supports_synchronized_output<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+) -> std::io::Result<bool>
+where
+    Output: Write,
+    Input: platform::RawOs + Read,
+{
+    let status = query_mode(output, input, Mode::SYNCHRONIZED_OUTPUT)?;
+
+    Ok(status.is_some_and(|status| status.is_supported()))
+}
+
+/// This function tells the terminal to hold rendering until `end_synchronized_update` is called.
+/// Terminals without synchronized output support might display this sequence, see
+/// `supports_synchronized_output`.
+pub fn begin_synchronized_update<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    set_mode(output, Mode::SYNCHRONIZED_OUTPUT)
+}
+
+/// This function tells the terminal to render everything written since
+/// `begin_synchronized_update`.
+pub fn end_synchronized_update<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    reset_mode(output, Mode::SYNCHRONIZED_OUTPUT)
+}
+
+/// This function wraps all writes made by `frame` in a synchronized update and flushes the output
+/// afterwards, so the terminal draws the frame at once instead of tearing. When `supported` is
+/// false, see `supports_synchronized_output`, the sequences are skipped and the frame is only
+/// flushed. The update is ended even if `frame` fails.
+pub fn synchronized_update<Output, F, T>(
+    output: &mut Output,
+    supported: bool,
+    frame: F,
+) -> std::io::Result<T>
+where
+    Output: Write,
+    F: FnOnce(&mut Output) -> std::io::Result<T>,
+{
+    if supported {
+        begin_synchronized_update(output)?;
+    }
+
+    let result = frame(output);
+
+    if supported {
+        end_synchronized_update(output)?;
+    }
+
+    output.flush()?;
+
+    result
+}
+
+/// This function queries the default foreground, default background or cursor color using OSC 10,
+/// 11 or 12. Like `get_cursor_position` this requires the application be in raw mode and can block
+/// for up to 1 second. `None` is returned if the terminal does not reply.
+pub fn query_dynamic_color<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    color: DynamicColor,
+) -> std::io::Result<Option<Rgb>>
+where
+    Output: Write,
+    Input: platform::RawOs + Read,
+{
+    platform::query_dynamic_color(output, input, color)
+}
+
+/// This function sets the default foreground, default background or cursor color using OSC 10, 11
+/// or 12
+pub fn set_dynamic_color<Output>(
+    output: &mut Output,
+    color: DynamicColor,
+    rgb: Rgb,
+) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_dynamic_color(output, color, rgb)
+}
+
+/// This function resets the default foreground, default background or cursor color to the
+/// terminal default using OSC 110, 111 or 112
+pub fn reset_dynamic_color<Output>(output: &mut Output, color: DynamicColor) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_dynamic_color(output, color)
+}
+
+/// This function queries an entry of the 256 color palette using OSC 4, for example to find out
+/// which color `Color::Red` is displayed as. Like `get_cursor_position` this requires the
+/// application be in raw mode and can block for up to 1 second. `None` is returned if the terminal
+/// does not reply.
+pub fn query_palette_color<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    index: u8,
+) -> std::io::Result<Option<Rgb>>
+where
+    Output: Write,
+    Input: platform::RawOs + Read,
+{
+    platform::query_palette_color(output, input, index)
+}
+
+/// This function changes an entry of the 256 color palette using OSC 4. The change outlives the
+/// application, see `PaletteRecord` or `Terminal::set_palette_color` to restore changed entries
+/// on exit.
+pub fn set_palette_color<Output>(output: &mut Output, index: u8, rgb: Rgb) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_palette_color(output, index, rgb)
+}
+
+/// This function resets a single palette entry to the terminal default using OSC 104
+pub fn reset_palette_color<Output>(output: &mut Output, index: u8) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_palette_color(output, index)
+}
+
+/// This function resets every palette entry to the terminal default using OSC 104
+pub fn reset_palette<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_palette(output)
+}
+
+/// This function queries the color scheme of the terminal. Terminals supporting color scheme
+/// reports are asked directly, otherwise the default background color is classified with
+/// `Rgb::color_scheme`. `None` is returned if the terminal supports neither. To be notified when
+/// the scheme changes, set `Mode::COLOR_SCHEME_UPDATES` and handle `Event::ColorSchemeChanged`.
+pub fn query_color_scheme<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+) -> std::io::Result<Option<ColorScheme>>
+where
+    Output: Write,
+    Input: platform::RawOs + Read,
+{
+    let supported = query_mode(output, input, Mode::COLOR_SCHEME_UPDATES)?
+        .is_some_and(|status| status != ModeStatus::NotRecognized);
+
+    if supported {
+        if let Some(scheme) = platform::query_color_scheme(output, input)? {
+            return Ok(Some(scheme));
+        }
+    }
+
+    let background = query_dynamic_color(output, input, DynamicColor::Background)?;
+
+    Ok(background.map(|background| background.color_scheme()))
+}
+
+/// This function moves the cursor to a given 0-based position.
+pub fn move_cursor<Output>(output: &mut Output, position: Position) -> std::io::Result<()>
 where
     Output: Write,
 {
-    platform::move_cursor(output, line, column)
+    platform::move_cursor(output, position)
 }
 
 /// This function hides the cursor
@@ -126,8 +341,104 @@ where
     platform::show_cursor(output)
 }
 
+/// This function moves the cursor up by `count` lines, stopping at the top of the screen
+pub fn move_cursor_up<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_up(output, count)
+}
+
+/// This function moves the cursor down by `count` lines, stopping at the bottom of the screen
+pub fn move_cursor_down<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_down(output, count)
+}
+
+/// This function moves the cursor right by `count` columns, stopping at the edge of the screen
+pub fn move_cursor_right<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_right(output, count)
+}
+
+/// This function moves the cursor left by `count` columns, stopping at the edge of the screen
+pub fn move_cursor_left<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_left(output, count)
+}
+
+/// This function moves the cursor to the start of the line `count` lines down
+pub fn move_cursor_to_next_line<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_to_next_line(output, count)
+}
+
+/// This function moves the cursor to the start of the line `count` lines up
+pub fn move_cursor_to_previous_line<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_to_previous_line(output, count)
+}
+
+/// This function moves the cursor to a 0-based column of the current line
+pub fn move_cursor_to_column<Output>(output: &mut Output, column: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::move_cursor_to_column(output, column)
+}
+
+/// This function saves the cursor position, the current style and the origin mode with DECSC
+pub fn save_cursor<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::save_cursor(output)
+}
+
+/// This function restores what was saved by `save_cursor` with DECRC
+pub fn restore_cursor<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::restore_cursor(output)
+}
+
+/// This function sets the shape of the cursor and whether it blinks with DECSCUSR
+pub fn set_cursor_shape<Output>(output: &mut Output, shape: CursorShape) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_cursor_shape(output, shape)
+}
+
+/// This function sets the color of the cursor using OSC 12
+pub fn set_cursor_color<Output>(output: &mut Output, rgb: Rgb) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_dynamic_color(output, DynamicColor::Cursor, rgb)
+}
+
+/// This function resets the color of the cursor to the terminal default using OSC 112
+pub fn reset_cursor_color<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_dynamic_color(output, DynamicColor::Cursor)
+}
+
 /// This function returns the size of an output in rows and columns
-pub fn get_size<Output>(output: &Output) -> std::io::Result<(u16, u16)>
+pub fn get_size<Output>(output: &Output) -> std::io::Result<Size>
 where
     Output: platform::RawOs,
 {
@@ -142,6 +453,132 @@ where
     platform::erase_entire_screen(output)
 }
 
+/// This function limits scrolling to the 0-based rows from `top` to `bottom` (inclusive). The
+/// cursor moves to the top left corner of the screen
+pub fn set_scroll_region<Output>(output: &mut Output, top: u16, bottom: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::set_scroll_region(output, top, bottom)
+}
+
+/// This function lets the entire screen scroll again. The cursor moves to the top left corner
+/// of the screen
+pub fn reset_scroll_region<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::reset_scroll_region(output)
+}
+
+/// This function scrolls the contents of the scroll region up, adding blank lines at the bottom
+pub fn scroll_up<Output>(output: &mut Output, lines: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::scroll_up(output, lines)
+}
+
+/// This function scrolls the contents of the scroll region down, adding blank lines at the top
+pub fn scroll_down<Output>(output: &mut Output, lines: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::scroll_down(output, lines)
+}
+
+/// This function inserts blank lines at the cursor, moving the lines below it down within the
+/// scroll region
+pub fn insert_lines<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::insert_lines(output, count)
+}
+
+/// This function deletes lines starting at the cursor, moving the lines below it up within the
+/// scroll region
+pub fn delete_lines<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::delete_lines(output, count)
+}
+
+/// This function inserts blank characters at the cursor, moving the rest of the line right
+pub fn insert_chars<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::insert_chars(output, count)
+}
+
+/// This function deletes characters starting at the cursor, moving the rest of the line left
+pub fn delete_chars<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::delete_chars(output, count)
+}
+
+/// This function erases characters starting at the cursor without moving the rest of the line
+pub fn erase_chars<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_chars(output, count)
+}
+
+/// This function erases the line the cursor is on
+pub fn erase_line<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_line(output)
+}
+
+/// This function erases from the cursor to the end of the line
+pub fn erase_to_end_of_line<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_to_end_of_line(output)
+}
+
+/// This function erases from the start of the line to the cursor
+pub fn erase_to_start_of_line<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_to_start_of_line(output)
+}
+
+/// This function erases from the cursor to the end of the screen
+pub fn erase_below<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_below(output)
+}
+
+/// This function erases from the start of the screen to the cursor
+pub fn erase_above<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::erase_above(output)
+}
+
+/// This function deletes the scrollback, keeping the contents of the screen
+pub fn clear_scrollback<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    platform::clear_scrollback(output)
+}
+
+/// This function sets the foreground color. Named, bright, 256 color and truecolor values are
+/// supported, see `Color`
 pub fn set_fg<Output>(
     output: &mut Output,
     color: impl ColorCode<ForegroundCode>,
@@ -152,6 +589,8 @@ where
     write!(output, "\x1b[{}m", color.code())
 }
 
+/// This function sets the background color. Named, bright, 256 color and truecolor values are
+/// supported, see `Color`
 pub fn set_bg<Output>(
     output: &mut Output,
     color: impl ColorCode<BackgroundCode>,
@@ -159,5 +598,125 @@ pub fn set_bg<Output>(
 where
     Output: Write,
 {
-    write!(output, "\x1b{}m", color.code())
+    write!(output, "\x1b[{}m", color.code())
+}
+
+/// This function sets the color of underlines independently of the foreground color. Nothing is
+/// written if `capabilities` does not report underline color support, as unsupported terminals
+/// might print the sequence.
+pub fn set_underline_color<Output>(
+    output: &mut Output,
+    capabilities: &Capabilities,
+    color: impl ColorCode<UnderlineCode>,
+) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    if !capabilities.underline_color {
+        return Ok(());
+    }
+
+    write!(output, "\x1b[{}m", color.code())
+}
+
+/// This function resets the underline color to follow the foreground color. Nothing is written if
+/// `capabilities` does not report underline color support.
+pub fn reset_underline_color<Output>(
+    output: &mut Output,
+    capabilities: &Capabilities,
+) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    if !capabilities.underline_color {
+        return Ok(());
+    }
+
+    write!(output, "\x1b[59m")
+}
+
+/// This function enables a single text attribute
+pub fn set_attribute<Output>(output: &mut Output, attribute: Attribute) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b[{}m", attribute.code())
+}
+
+/// This function disables a single text attribute without affecting colors or other attributes.
+/// Attributes sharing a reset code are disabled together, see `Attribute::reset_code`
+pub fn reset_attribute<Output>(output: &mut Output, attribute: Attribute) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b[{}m", attribute.reset_code())
+}
+
+/// This function enables every attribute in a set using a single sequence
+pub fn set_attributes<Output>(output: &mut Output, attributes: Attributes) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    if attributes.is_empty() {
+        return Ok(());
+    }
+
+    let codes: Vec<&str> = attributes.iter().map(|i| i.code()).collect();
+
+    write!(output, "\x1b[{}m", codes.join(";"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns what a function writes
+    fn written(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> String {
+        let mut output = Vec::new();
+        write(&mut output).unwrap();
+
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn editing_by_zero_writes_nothing() {
+        assert_eq!(written(|o| scroll_up(o, 0)), "");
+        assert_eq!(written(|o| scroll_down(o, 0)), "");
+        assert_eq!(written(|o| insert_lines(o, 0)), "");
+        assert_eq!(written(|o| delete_lines(o, 0)), "");
+        assert_eq!(written(|o| insert_chars(o, 0)), "");
+        assert_eq!(written(|o| delete_chars(o, 0)), "");
+        assert_eq!(written(|o| erase_chars(o, 0)), "");
+
+        assert_eq!(written(|o| scroll_up(o, 3)), "\x1b[3S");
+        assert_eq!(written(|o| erase_chars(o, 1)), "\x1b[1X");
+    }
+
+    #[test]
+    fn cursor_movement_by_zero_writes_nothing() {
+        assert_eq!(written(|o| move_cursor_up(o, 0)), "");
+        assert_eq!(written(|o| move_cursor_down(o, 0)), "");
+        assert_eq!(written(|o| move_cursor_right(o, 0)), "");
+        assert_eq!(written(|o| move_cursor_left(o, 0)), "");
+        assert_eq!(written(|o| move_cursor_to_next_line(o, 0)), "");
+        assert_eq!(written(|o| move_cursor_to_previous_line(o, 0)), "");
+
+        assert_eq!(written(|o| move_cursor_up(o, 2)), "\x1b[2A");
+        assert_eq!(written(|o| move_cursor_to_previous_line(o, 1)), "\x1b[1F");
+    }
+
+    #[test]
+    fn coordinates_do_not_overflow() {
+        let position = Position::new(u16::MAX, u16::MAX);
+
+        assert_eq!(written(|o| move_cursor(o, position)), "\x1b[65536;65536H");
+        assert_eq!(
+            written(|o| move_cursor_to_column(o, u16::MAX)),
+            "\x1b[65536G"
+        );
+        assert_eq!(
+            written(|o| set_scroll_region(o, 0, u16::MAX)),
+            "\x1b[1;65536r"
+        );
+    }
 }
@@ -0,0 +1,91 @@
+use std::io::{Read, Write};
+
+use crate::style::Rgb;
+
+use super::platform::RawOs;
+
+/// A record of palette entries changed by an application, so they can be restored on exit.
+///
+/// Entries changed with `set` are reset to the terminal default when restored. Entries changed with
+/// `set_preserving` query their current value first and are restored to exactly that value, which
+/// also preserves changes made by the user or other applications.
+///
+/// Only changes made through the record are restored, and nothing is restored automatically.
+/// `Terminal::set_palette_color` records into the terminal's own record, which is restored along
+/// with the modes when the terminal is restored or dropped.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaletteRecord {
+    changed: Vec<(u8, Option<Rgb>)>,
+}
+
+impl PaletteRecord {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns whether any palette entry has been changed since the last restore
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty()
+    }
+
+    /// Returns the indices of changed palette entries
+    pub fn changed(&self) -> impl Iterator<Item = u8> + '_ {
+        self.changed.iter().map(|(index, _)| *index)
+    }
+
+    /// Changes a palette entry and records it to be reset to the terminal default
+    pub fn set<Output>(&mut self, output: &mut Output, index: u8, rgb: Rgb) -> std::io::Result<()>
+    where
+        Output: Write,
+    {
+        self.record(index, None);
+
+        super::set_palette_color(output, index, rgb)
+    }
+
+    /// Changes a palette entry and records its current value to be restored. This queries the
+    /// terminal, see `query_palette_color`, unless the entry was already changed. If the terminal
+    /// does not reply the entry is reset to the terminal default instead
+    pub fn set_preserving<Output, Input>(
+        &mut self,
+        output: &mut Output,
+        input: &mut Input,
+        index: u8,
+        rgb: Rgb,
+    ) -> std::io::Result<()>
+    where
+        Output: Write,
+        Input: RawOs + Read,
+    {
+        if !self.changed().any(|i| i == index) {
+            let original = super::query_palette_color(output, input, index)?;
+
+            self.record(index, original);
+        }
+
+        super::set_palette_color(output, index, rgb)
+    }
+
+    /// Restores every changed entry and clears the record
+    pub fn restore<Output>(&mut self, output: &mut Output) -> std::io::Result<()>
+    where
+        Output: Write,
+    {
+        for (index, original) in self.changed.drain(..) {
+            match original {
+                Some(rgb) => super::set_palette_color(output, index, rgb)?,
+                None => super::reset_palette_color(output, index)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Keeps the first recorded original of an entry, later changes do not affect what is
+    /// restored
+    fn record(&mut self, index: u8, original: Option<Rgb>) {
+        if !self.changed().any(|i| i == index) {
+            self.changed.push((index, original));
+        }
+    }
+}
@@ -9,11 +9,18 @@ use libc::{
     POLLIN, TCSAFLUSH, TIOCGWINSZ,
 };
 
-use crate::events::{
-    unix::{parse_batch, parse_event},
-    Event, EventBatch,
+use crate::{
+    events::{
+        unix::{parse_batch, parse_event},
+        DynamicColor, Event, EventBatch,
+    },
+    geometry::{Position, Size},
+    mode::{Mode, ModeStatus},
+    style::{ColorScheme, Rgb},
 };
 
+use super::CursorShape;
+
 pub trait RawOs: std::os::fd::AsRawFd {}
 
 impl<T> RawOs for T where T: std::os::fd::AsRawFd {}
@@ -53,7 +60,7 @@ where
 
     opts.c_lflag &= !(libc::ECHO | libc::ICANON);
 
-    if unsafe { libc::tcsetattr(fd, TCSAFLUSH, &mut opts) } == -1 {
+    if unsafe { libc::tcsetattr(fd, TCSAFLUSH, &opts) } == -1 {
         return Err(Error::last_os_error());
     }
 
@@ -74,7 +81,7 @@ where
 
     opts.c_lflag |= libc::ECHO | libc::ICANON;
 
-    if unsafe { libc::tcsetattr(fd, TCSAFLUSH, &mut opts) } == -1 {
+    if unsafe { libc::tcsetattr(fd, TCSAFLUSH, &opts) } == -1 {
         return Err(Error::last_os_error());
     }
 
@@ -95,6 +102,8 @@ where
     write!(output, "\x1b[?1049l")
 }
 
+// Reading byte by byte is intentional here, see `utils::read_single`
+#[allow(clippy::unbuffered_bytes)]
 pub fn read_single<Input>(input: &mut Input) -> std::io::Result<Option<Event>>
 where
     Input: Read,
@@ -170,7 +179,7 @@ where
 pub fn get_cursor_position<Output, Input>(
     output: &mut Output,
     input: &mut Input,
-) -> std::io::Result<Option<(u16, u16)>>
+) -> std::io::Result<Option<Position>>
 where
     Output: Write,
     Input: AsRawFd + Read,
@@ -179,6 +188,19 @@ where
 
     output.flush()?;
 
+    wait_for_reply(input, |ev| match ev {
+        Event::Cursor(position) => Some(position),
+        _ => None,
+    })
+}
+
+/// Reads events until `filter` accepts one or 1 second has passed. Events that are not accepted
+/// are discarded.
+fn wait_for_reply<Input, T, F>(input: &mut Input, mut filter: F) -> std::io::Result<Option<T>>
+where
+    Input: AsRawFd + Read,
+    F: FnMut(Event) -> Option<T>,
+{
     let start = std::time::Instant::now();
 
     loop {
@@ -191,20 +213,202 @@ where
         let batch = read_batch_blocking(input, 1000 - passed)?;
 
         for ev in batch.into_iter() {
-            if let Event::Cursor(x, y) = ev {
-                return Ok(Some((x, y)));
+            if let Some(reply) = filter(ev) {
+                return Ok(Some(reply));
             }
         }
     }
 
-    return Ok(None);
+    Ok(None)
+}
+
+pub fn set_mode<Output>(output: &mut Output, mode: Mode) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    match mode {
+        Mode::Ansi(value) => write!(output, "\x1b[{}h", value),
+        Mode::Dec(value) => write!(output, "\x1b[?{}h", value),
+    }
+}
+
+pub fn reset_mode<Output>(output: &mut Output, mode: Mode) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    match mode {
+        Mode::Ansi(value) => write!(output, "\x1b[{}l", value),
+        Mode::Dec(value) => write!(output, "\x1b[?{}l", value),
+    }
+}
+
+pub fn request_mode<Output>(output: &mut Output, mode: Mode) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    match mode {
+        Mode::Ansi(value) => write!(output, "\x1b[{}$p", value),
+        Mode::Dec(value) => write!(output, "\x1b[?{}$p", value),
+    }
+}
+
+pub fn query_mode<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    mode: Mode,
+) -> std::io::Result<Option<ModeStatus>>
+where
+    Output: Write,
+    Input: AsRawFd + Read,
+{
+    request_mode(output, mode)?;
+
+    output.flush()?;
+
+    wait_for_reply(input, |ev| match ev {
+        Event::ModeReport(reported, status) if reported == mode => Some(status),
+        _ => None,
+    })
+}
+
+pub fn request_dynamic_color<Output>(
+    output: &mut Output,
+    color: DynamicColor,
+) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b]{};?\x1b\\", color.code())
+}
+
+pub fn query_dynamic_color<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    color: DynamicColor,
+) -> std::io::Result<Option<Rgb>>
+where
+    Output: Write,
+    Input: AsRawFd + Read,
+{
+    request_dynamic_color(output, color)?;
+
+    output.flush()?;
+
+    wait_for_reply(input, |ev| match ev {
+        Event::DynamicColor(reported, rgb) if reported == color => Some(rgb),
+        _ => None,
+    })
+}
+
+pub fn set_dynamic_color<Output>(
+    output: &mut Output,
+    color: DynamicColor,
+    rgb: Rgb,
+) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(
+        output,
+        "\x1b]{};rgb:{:02x}/{:02x}/{:02x}\x1b\\",
+        color.code(),
+        rgb.r,
+        rgb.g,
+        rgb.b
+    )
+}
+
+pub fn reset_dynamic_color<Output>(output: &mut Output, color: DynamicColor) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b]{}\x1b\\", color.code() + 100)
+}
+
+pub fn request_palette_color<Output>(output: &mut Output, index: u8) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b]4;{};?\x1b\\", index)
 }
 
-pub fn move_cursor<Output>(output: &mut Output, line: u16, column: u16) -> std::io::Result<()>
+pub fn query_palette_color<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+    index: u8,
+) -> std::io::Result<Option<Rgb>>
 where
     Output: Write,
+    Input: AsRawFd + Read,
 {
-    write!(output, "\x1b[{};{}H", line, column)
+    request_palette_color(output, index)?;
+
+    output.flush()?;
+
+    wait_for_reply(input, |ev| match ev {
+        Event::PaletteColor(reported, rgb) if reported == index => Some(rgb),
+        _ => None,
+    })
+}
+
+pub fn set_palette_color<Output>(output: &mut Output, index: u8, rgb: Rgb) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(
+        output,
+        "\x1b]4;{};rgb:{:02x}/{:02x}/{:02x}\x1b\\",
+        index, rgb.r, rgb.g, rgb.b
+    )
+}
+
+pub fn reset_palette_color<Output>(output: &mut Output, index: u8) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b]104;{}\x1b\\", index)
+}
+
+pub fn reset_palette<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b]104\x1b\\")
+}
+
+pub fn request_color_scheme<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b[?996n")
+}
+
+pub fn query_color_scheme<Output, Input>(
+    output: &mut Output,
+    input: &mut Input,
+) -> std::io::Result<Option<ColorScheme>>
+where
+    Output: Write,
+    Input: AsRawFd + Read,
+{
+    request_color_scheme(output)?;
+
+    output.flush()?;
+
+    wait_for_reply(input, |ev| match ev {
+        Event::ColorSchemeChanged(scheme) => Some(scheme),
+        _ => None,
+    })
+}
+
+pub fn move_cursor<Output>(output: &mut Output, position: Position) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    // Terminals use 1-based coordinates
+    let (row, col) = (u32::from(position.row) + 1, u32::from(position.col) + 1);
+
+    write!(output, "\x1b[{};{}H", row, col)
 }
 
 pub fn hide_cursor<Output>(output: &mut Output) -> std::io::Result<()>
@@ -221,7 +425,77 @@ where
     write!(output, "\x1b[?25h")
 }
 
-pub fn get_size<Output>(output: &Output) -> std::io::Result<(u16, u16)>
+pub fn move_cursor_up<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'A')
+}
+
+pub fn move_cursor_down<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'B')
+}
+
+pub fn move_cursor_right<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'C')
+}
+
+pub fn move_cursor_left<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'D')
+}
+
+pub fn move_cursor_to_next_line<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'E')
+}
+
+pub fn move_cursor_to_previous_line<Output>(output: &mut Output, count: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write_counted(output, count, 'F')
+}
+
+pub fn move_cursor_to_column<Output>(output: &mut Output, column: u16) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b[{}G", u32::from(column) + 1)
+}
+
+pub fn save_cursor<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b7")
+}
+
+pub fn restore_cursor<Output>(output: &mut Output) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b8")
+}
+
+pub fn set_cursor_shape<Output>(output: &mut Output, shape: CursorShape) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    write!(output, "\x1b[{} q", shape.code())
+}
+
+pub fn get_size<Output>(output: &Output) -> std::io::Result<Size>
 where
     Output: AsRawFd,
 {
@@ -233,7 +507,7 @@ where
         return Err(Error::last_os_error());
     }
 
-    Ok((size.ws_row, size.ws_col))
+    Ok(Size::new(size.ws_row, size.ws_col))
 }
 
 pub fn erase_entire_screen<S>(s: &mut S) -> std::io::Result<()>
@@ -242,3 +516,121 @@ where
 {
     write!(s, "\x1b[2J")
 }
+
+pub fn set_scroll_region<S>(s: &mut S, top: u16, bottom: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[{};{}r", u32::from(top) + 1, u32::from(bottom) + 1)
+}
+
+pub fn reset_scroll_region<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[r")
+}
+
+/// Writes a CSI sequence taking a count, such as CUU or SU. Nothing is written for a count of 0
+/// since terminals treat it as 1
+fn write_counted<Output>(output: &mut Output, count: u16, final_byte: char) -> std::io::Result<()>
+where
+    Output: Write,
+{
+    if count == 0 {
+        return Ok(());
+    }
+
+    write!(output, "\x1b[{}{}", count, final_byte)
+}
+
+pub fn scroll_up<S>(s: &mut S, lines: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, lines, 'S')
+}
+
+pub fn scroll_down<S>(s: &mut S, lines: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, lines, 'T')
+}
+
+pub fn insert_lines<S>(s: &mut S, count: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, count, 'L')
+}
+
+pub fn delete_lines<S>(s: &mut S, count: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, count, 'M')
+}
+
+pub fn insert_chars<S>(s: &mut S, count: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, count, '@')
+}
+
+pub fn delete_chars<S>(s: &mut S, count: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, count, 'P')
+}
+
+pub fn erase_chars<S>(s: &mut S, count: u16) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write_counted(s, count, 'X')
+}
+
+pub fn erase_line<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[2K")
+}
+
+pub fn erase_to_end_of_line<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[K")
+}
+
+pub fn erase_to_start_of_line<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[1K")
+}
+
+pub fn erase_below<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[J")
+}
+
+pub fn erase_above<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[1J")
+}
+
+pub fn clear_scrollback<S>(s: &mut S) -> std::io::Result<()>
+where
+    S: Write,
+{
+    write!(s, "\x1b[3J")
+}